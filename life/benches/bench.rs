@@ -13,7 +13,9 @@ mod tests {
     const INPUT: &'static [u8] = include_bytes!("../src/life.txt");
     const N: usize = 100;
 
+    use life::bitgame;
     use life::game;
+    use life::hashlife;
     use life::ndgame;
 
     use test::Bencher;
@@ -43,4 +45,26 @@ mod tests {
         })
     }
 
+    #[bench]
+    fn bench_bitgame(b: &mut Bencher)
+    {
+        let board = bitgame::Board::parse(INPUT);
+        b.iter(|| {
+            let mut b = board.clone();
+            for _ in 0..100 {
+                b.iterate();
+            }
+        })
+    }
+
+    #[bench]
+    fn bench_hashlife(b: &mut Bencher)
+    {
+        let board = game::Board::parse(INPUT);
+        b.iter(|| {
+            let mut u = hashlife::Universe::from_board(&board);
+            u.advance(100);
+        })
+    }
+
 }
@@ -0,0 +1,119 @@
+// The interface shared by the different board implementations, so that code which only needs to
+// look at and step a board doesn't care how it is stored.
+//
+// Coordinates are signed so that unbounded boards can extend in every direction. Bounded boards
+// treat everything outside them as dead.
+
+use std::cmp;
+
+use rule::Rule;
+
+// A rectangle of cells, given by its top left corner and size.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bounds {
+  pub r: i64,
+  pub c: i64,
+  pub rows: usize,
+  pub cols: usize,
+}
+
+impl Bounds {
+  pub fn new(r: i64, c: i64, rows: usize, cols: usize) -> Bounds {
+    Bounds { r: r, c: c, rows: rows, cols: cols }
+  }
+
+  pub fn contains(&self, r: i64, c: i64) -> bool {
+    r >= self.r && r < self.r + self.rows as i64 && c >= self.c && c < self.c + self.cols as i64
+  }
+}
+
+pub trait Automaton {
+  fn get(&self, r: i64, c: i64) -> bool;
+
+  // The state of a cell under a Generations rule: 0 when dead, 1 when alive and 2 and up while
+  // decaying (see `Rule`). Boards that only store whether cells are alive have no decay states.
+  fn state(&self, r: i64, c: i64) -> u8 {
+    self.get(r, c) as u8
+  }
+
+  // Bounded boards panic when asked to set a cell outside of them.
+  fn set(&mut self, r: i64, c: i64, alive: bool);
+
+  // Advances the board by one generation.
+  fn step(&mut self);
+
+  fn population(&self) -> u64;
+
+  fn generation(&self) -> u64;
+
+  // Changes the generation number without touching the cells. `History` puts back an earlier
+  // state by restoring its cells itself and then calling this with that state's generation.
+  fn set_generation(&mut self, generation: u64);
+
+  // The region holding the board's cells: the whole board if it is bounded, or the bounding box
+  // of the live cells if it isn't.
+  fn bounds(&self) -> Bounds;
+
+  fn rule(&self) -> Rule;
+
+  // Coordinates of every live cell in row-major order. Unbounded boards should override this to
+  // avoid scanning their whole bounding box.
+  fn live_cells(&self) -> Vec<(i64, i64)> {
+    let bounds = self.bounds();
+    let mut cells = Vec::new();
+    for r in bounds.r..bounds.r + bounds.rows as i64 {
+      for c in bounds.c..bounds.c + bounds.cols as i64 {
+        if self.get(r, c) {
+          cells.push((r, c));
+        }
+      }
+    }
+    cells
+  }
+}
+
+// The smallest rectangle containing both `a` and `b`.
+pub fn union(a: Bounds, b: Bounds) -> Bounds {
+  if a.rows == 0 || a.cols == 0 {
+    return b;
+  }
+  if b.rows == 0 || b.cols == 0 {
+    return a;
+  }
+  let r = cmp::min(a.r, b.r);
+  let c = cmp::min(a.c, b.c);
+  let r_end = cmp::max(a.r + a.rows as i64, b.r + b.rows as i64);
+  let c_end = cmp::max(a.c + a.cols as i64, b.c + b.cols as i64);
+  Bounds::new(r, c, (r_end - r) as usize, (c_end - c) as usize)
+}
+
+// The smallest rectangle containing every live cell of `board`, or None if there aren't any.
+pub fn live_bounds<A: Automaton>(board: &A) -> Option<Bounds> {
+  bounds_of(&board.live_cells())
+}
+
+// The smallest rectangle containing all of `cells`, or None if there aren't any.
+pub fn bounds_of(cells: &[(i64, i64)]) -> Option<Bounds> {
+  if cells.is_empty() {
+    return None;
+  }
+  let r0 = cells.iter().map(|&(r, _)| r).min().unwrap();
+  let r1 = cells.iter().map(|&(r, _)| r).max().unwrap();
+  let c0 = cells.iter().map(|&(_, c)| c).min().unwrap();
+  let c1 = cells.iter().map(|&(_, c)| c).max().unwrap();
+  Some(Bounds::new(r0, c0, (r1 - r0 + 1) as usize, (c1 - c0 + 1) as usize))
+}
+
+// Counts the cells that differ between two boards.
+pub fn difference<A: Automaton, B: Automaton>(a: &A, b: &B) -> usize {
+  let bounds = union(a.bounds(), b.bounds());
+  let mut diff = 0;
+  for r in bounds.r..bounds.r + bounds.rows as i64 {
+    for c in bounds.c..bounds.c + bounds.cols as i64 {
+      if a.get(r, c) != b.get(r, c) {
+        diff += 1;
+      }
+    }
+  }
+  diff
+}
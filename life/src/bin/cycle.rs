@@ -0,0 +1,61 @@
+// Runs pattern files on an unbounded board until they repeat, and reports what they turn into.
+
+extern crate getopts;
+use getopts::Options;
+
+extern crate life;
+use life::cycle::{self, Kind};
+use life::format;
+use life::options;
+use life::spaceship::Velocity;
+use life::sparse;
+
+use std::env;
+use std::path::Path;
+use std::process;
+
+fn main() {
+  let args: Vec<String> = env::args().collect();
+  let mut opts = Options::new();
+  options::add_rule(&mut opts);
+  opts.optopt("g", "max-generations", "give up after this many generations (default 10000)", "N");
+  let matches = match opts.parse(&args[1..]) {
+    Ok(m) => m,
+    Err(e) => { println!("{}", e); process::exit(1); }
+  };
+  let rule = options::rule(&matches);
+  let max_generations: u64 = options::number(&matches, "max-generations", 10000);
+  if matches.free.is_empty() {
+    print!("{}", opts.usage(&format!("Usage: {} [options] PATTERN...", args[0])));
+    process::exit(1);
+  }
+
+  for path in matches.free.iter() {
+    let mut pattern = match format::load(Path::new(path)) {
+      Ok(pattern) => pattern,
+      Err(e) => { println!("{}", e); continue; }
+    };
+    let rule = rule.or(pattern.rule).unwrap_or_default();
+    if !rule.is_life_like() || rule.born(0) {
+      println!("{}: cycle only runs Life-like rules without birth on zero neighbors, not {}", path,
+               rule);
+      continue;
+    }
+    pattern.rule = Some(rule);
+    let board = sparse::Board::from_board(&pattern.to_board());
+
+    match cycle::find_cycle(&board, max_generations) {
+      Some(cycle) => {
+        let kind = match cycle.kind() {
+          Kind::Died => "dies out".to_string(),
+          Kind::StillLife => "still life".to_string(),
+          Kind::Oscillator => "oscillator".to_string(),
+          Kind::Spaceship => format!("{} spaceship", Velocity::of_cycle(&cycle).unwrap()),
+        };
+        println!("{}: {} after {} generations; period {}, displacement {:?}, population {}",
+                 path, kind, cycle.transient, cycle.period, cycle.displacement, cycle.population);
+      }
+      None => println!("{}: no cycle within {} generations", path, max_generations),
+    }
+  }
+}
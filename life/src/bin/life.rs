@@ -1,43 +1,230 @@
+extern crate getopts;
+use getopts::Options;
+
 extern crate sdl2;
+use sdl2::event::Event;
+use sdl2::keyboard::Keycode;
+use sdl2::mouse::Mouse;
 use sdl2::pixels::Color;
 use sdl2::rect::Rect;
 
 extern crate life;
+use life::automaton::Automaton;
+use life::format;
 use life::game::Board;
-use life::gui::BoardRenderer;
+use life::gui::{BoardRect, BoardRenderer};
+use life::history::History;
+use life::options;
+use life::seed;
+
+use std::cmp;
+use std::env;
+use std::path::Path;
+use std::process;
+use std::time::Instant;
+
+const ROWS: usize = 100;
+const COLS: usize = 100;
+const DENSITY: f32 = 0.47;
+
+const DEFAULT_DELAY: u32 = 50;
+const MIN_DELAY: u32 = 1;
+const MAX_DELAY: u32 = 2000;
+
+// How much one notch of the mouse wheel zooms by.
+const ZOOM_STEP: f64 = 1.25;
 
 fn main() {
+  let args: Vec<String> = env::args().collect();
+  let mut opts = Options::new();
+  options::add_rule(&mut opts);
+  opts.optopt("s", "seed", "seed for the random boards (default: random)", "SEED");
+  let matches = match opts.parse(&args[1..]) {
+    Ok(m) => m,
+    Err(e) => { println!("{}", e); process::exit(1); }
+  };
+  let rule = options::rule(&matches);
+  let seed: u64 = options::number(&matches, "seed", seed::random_seed());
+  let mut rng = seed::seeded_rng(seed);
+
+  // An optional pattern file (RLE, plaintext, Life 1.05/1.06 or a `#`/`.` grid) to start from
+  // instead of a random board.
+  let mut pattern = match matches.free.first() {
+    Some(path) => match format::load(Path::new(path)) {
+      Ok(pattern) => Some(pattern),
+      Err(e) => { println!("{}", e); process::exit(1); }
+    },
+    None => None,
+  };
+  let rule = rule.or(pattern.as_ref().and_then(|pattern| pattern.rule)).unwrap_or_default();
+  options::require_two_states(rule);
+  if let Some(ref mut pattern) = pattern {
+    pattern.rule = Some(rule);
+  }
+  let mut start = || match pattern {
+    Some(ref pattern) => pattern.to_board_centered(ROWS, COLS),
+    None => Board::random(ROWS, COLS, DENSITY, &mut rng).with_rule(rule),
+  };
+  if pattern.is_none() {
+    println!("Seed: {}", seed);
+  }
+
   let sdl_context = sdl2::init().unwrap();
   let video_ctx = sdl_context.video().unwrap();
 
   let window = sdl2::video::WindowBuilder::new(&video_ctx, "My window", 640, 640).build().unwrap();
   let mut renderer = window.renderer().present_vsync().build().unwrap();
 
-  let mut life = Board::random();
-  let board_renderer = BoardRenderer::new(Rect::new(0, 0, 640, 640));
+  println!("Left mouse button draws, right erases. Space pauses, n steps, b steps back, c clears, \
+            r resets and +/- change the speed. Type a generation and press g to go to it. m \
+            bookmarks the generation and [ and ] go to the previous and next bookmarks. Arrow \
+            keys or dragging with the middle button pan, the wheel zooms and Home shows the whole \
+            board.");
+
+  let mut life = History::new(start());
+  let mut board_renderer = BoardRenderer::new(Rect::new(0, 0, 640, 640));
+  // Where a middle button drag started, and the view at the time.
+  let mut drag: Option<(i32, i32, BoardRect)> = None;
+  let mut mouse = (0, 0);
+  // Digits typed so far, for g.
+  let mut count: Option<u64> = None;
 
   let mut event_pump = sdl_context.event_pump().unwrap();
   let mut exit = false;
+  let mut paused = false;
+  // Milliseconds between generations while running.
+  let mut delay = DEFAULT_DELAY;
+  let mut last_step = Instant::now();
   while !exit {
     renderer.set_draw_color(Color::RGB(255, 255, 255));
     renderer.clear();
 
-    board_renderer.draw(&life, &mut renderer).unwrap();
+    board_renderer.draw(life.board(), &mut renderer).unwrap();
 
     renderer.present();
 
-    use sdl2::event::Event;
-    match event_pump.wait_event_timeout(50) {
-      Some(Event::KeyDown {..}) => {
-        println!("Resetting after {} generations", life.generation);
-        life = Board::random();
+    let timeout = if paused { 100 } else { delay.saturating_sub(millis_since(last_step)) };
+    let mut event = event_pump.wait_event_timeout(cmp::max(timeout, 1));
+    while let Some(e) = event {
+      match e {
+        Event::KeyDown {keycode: Some(key), ..} => match key {
+          _ if digit(key).is_some() => {
+            count = Some(count.unwrap_or(0).saturating_mul(10).saturating_add(digit(key).unwrap()));
+          }
+          Keycode::Space => { paused = !paused; }
+          Keycode::N => {
+            paused = true;
+            life.step();
+          }
+          Keycode::B | Keycode::Backspace => {
+            paused = true;
+            if !life.back() {
+              println!("Can't go back past generation {}", life.generation());
+            }
+          }
+          Keycode::G => {
+            paused = true;
+            let generation = count.take().unwrap_or(0);
+            if let Err(e) = life.goto(generation) {
+              println!("{}", e);
+            }
+          }
+          Keycode::M => {
+            life.toggle_bookmark();
+            println!("Bookmarks: {:?}", life.bookmarks());
+          }
+          Keycode::LeftBracket | Keycode::RightBracket => {
+            let bookmark = if key == Keycode::LeftBracket {
+              life.previous_bookmark()
+            } else {
+              life.next_bookmark()
+            };
+            if let Some(generation) = bookmark {
+              paused = true;
+              if let Err(e) = life.goto(generation) {
+                println!("{}", e);
+              }
+            }
+          }
+          Keycode::C => {
+            life.edit(|board| for (r, c) in board.live_cells() {
+              board.set(r, c, false);
+            });
+          }
+          Keycode::R => {
+            println!("Resetting after {} generations", life.generation());
+            life = History::new(start());
+          }
+          Keycode::Plus | Keycode::Equals | Keycode::KpPlus => {
+            delay = cmp::max(MIN_DELAY, delay / 2);
+          }
+          Keycode::Minus | Keycode::KpMinus => {
+            delay = cmp::min(MAX_DELAY, delay * 2);
+          }
+          Keycode::Left | Keycode::Right | Keycode::Up | Keycode::Down => {
+            let view = board_renderer.board_rect(life.board());
+            let (dr, dc) = ((view.rows / 8) as i64 + 1, (view.cols / 8) as i64 + 1);
+            let (dr, dc) = match key {
+              Keycode::Left => (0, -dc),
+              Keycode::Right => (0, dc),
+              Keycode::Up => (-dr, 0),
+              _ => (dr, 0),
+            };
+            board_renderer.pan(life.board(), dr, dc);
+          }
+          Keycode::Home => { board_renderer.set_board_rect(None); }
+          _ => ()
+        },
+        // The left button draws cells and the right button erases them.
+        Event::MouseButtonDown {mouse_btn, x, y, ..} => match mouse_btn {
+          Mouse::Left => paint(&mut life, &board_renderer, x, y, true),
+          Mouse::Right => paint(&mut life, &board_renderer, x, y, false),
+          Mouse::Middle => { drag = Some((x, y, board_renderer.board_rect(life.board()))); }
+          _ => ()
+        },
+        Event::MouseButtonUp {mouse_btn: Mouse::Middle, ..} => { drag = None; }
+        Event::MouseMotion {mousestate, x, y, ..} => {
+          mouse = (x, y);
+          if let Some((x0, y0, ref view)) = drag {
+            let (dr, dc) = board_renderer.pixels_to_cells(life.board(), x - x0, y - y0);
+            board_renderer.set_board_rect(Some(BoardRect::new(view.r - dr, view.c - dc, view.rows, view.cols)));
+          } else if mousestate.left() {
+            paint(&mut life, &board_renderer, x, y, true);
+          } else if mousestate.right() {
+            paint(&mut life, &board_renderer, x, y, false);
+          }
+        }
+        Event::MouseWheel {y, ..} => {
+          board_renderer.zoom(life.board(), ZOOM_STEP.powi(y), mouse.0, mouse.1);
+        }
+        Event::Quit {..} => { exit = true; }
+        Event::Window {win_event_id: we, ..} => { println!("{:?}", we); }
+        _ => ()
       }
-      Some(Event::Quit {..}) => { exit = true; }
-      Some(Event::Window {win_event_id: we, ..}) => { println!("{:?}", we); }
-      Some(e) => { println!("{:?}", e); }
-      _ => ()
+      event = event_pump.poll_event();
     }
 
-    life = life.next();
+    if !paused && millis_since(last_step) >= delay {
+      life.step();
+      last_step = Instant::now();
+    }
   }
 }
+
+fn millis_since(t: Instant) -> u32 {
+  let elapsed = t.elapsed();
+  (elapsed.as_secs() * 1000) as u32 + elapsed.subsec_nanos() / 1000000
+}
+
+// Sets the cell under pixel (x, y), if there is one.
+fn paint(life: &mut History<Board>, board_renderer: &BoardRenderer, x: i32, y: i32, alive: bool) {
+  if let Some((r, c)) = board_renderer.cell_at(life.board(), x, y) {
+    life.edit(|board| board.set(r, c, alive));
+  }
+}
+
+fn digit(key: Keycode) -> Option<u64> {
+  let digits = [Keycode::Num0, Keycode::Num1, Keycode::Num2, Keycode::Num3, Keycode::Num4,
+                Keycode::Num5, Keycode::Num6, Keycode::Num7, Keycode::Num8, Keycode::Num9];
+  digits.iter().position(|&k| k == key).map(|d| d as u64)
+}
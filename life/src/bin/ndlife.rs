@@ -1,18 +1,30 @@
 // Original example code taken from rust-ndarray project (MIT licensed).
 
+extern crate getopts;
+use getopts::Options;
+
 extern crate life;
 
+use life::automaton::Automaton;
+use life::census::{Catalogue, Census};
 use life::ndgame::*;
+use life::options;
+use life::seed;
+
+use std::env;
+use std::process;
 
 const N: usize = 100;
+const DENSITY: f32 = 0.5;
 
 fn render(a: &Board) {
     for row in a.arr.genrows() {
         for &x in row {
-            if x > 0 {
-                print!("#");
-            } else {
-                print!(".");
+            match x {
+                0 => print!("."),
+                1 => print!("#"),
+                // decaying cells of Generations rules
+                _ => print!("+"),
             }
         }
         println!("");
@@ -20,16 +32,47 @@ fn render(a: &Board) {
 }
 
 fn main() {
-    let mut b = Board::parse(INPUT, N, N);
+    let args: Vec<String> = env::args().collect();
+    let mut opts = Options::new();
+    options::add_rule(&mut opts);
+    opts.optopt("j", "threads", "number of threads to step with (default 1)", "N");
+    opts.optopt("s", "seed", "start from a random board with this seed instead of the built-in pattern", "SEED");
+    let matches = match opts.parse(&args[1..]) {
+        Ok(m) => m,
+        Err(e) => { println!("{}", e); process::exit(1); }
+    };
+    let rule = options::rule(&matches).unwrap_or_default();
+
+    let threads: usize = match matches.opt_str("threads").map(|s| s.parse()) {
+        Some(Ok(n)) if n > 0 => n,
+        Some(_) => { println!("--threads must be a positive number"); process::exit(1); }
+        None => 1,
+    };
+
+    let b = match matches.opt_str("seed").map(|s| s.parse()) {
+        Some(Ok(seed)) => Board::random(N, N, DENSITY, &mut seed::seeded_rng(seed)),
+        Some(Err(_)) => { println!("--seed must be a number"); process::exit(1); }
+        None => Board::parse(INPUT, N, N),
+    };
+    let mut b = b.with_rule(rule);
     let mut scratch = Board::scratch(N, N);
     let steps = 100;
     b.turn_on_corners();
     for _ in 0..steps {
-        b.iterate(&mut scratch);
+        if threads > 1 {
+            b.iterate_parallel(&mut scratch, threads);
+        } else {
+            b.iterate(&mut scratch);
+        }
         b.turn_on_corners();
         //render(&a);
     }
     render(&b);
-    let alive = b.arr.iter().filter(|&&x| x > 0).count();
+    let alive = b.population();
     println!("After {} steps there are {} cells alive", steps, alive);
+    // The census runs objects on sparse boards, which only take Life-like rules without B0.
+    if rule.is_life_like() && !rule.born(0) {
+        println!("");
+        println!("{}", Census::take(&b, &Catalogue::new(rule)));
+    }
 }
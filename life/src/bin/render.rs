@@ -0,0 +1,98 @@
+// Renders a pattern file to an image without opening a window: a PNG of one generation, or an
+// animated GIF of several.
+
+extern crate getopts;
+use getopts::Options;
+
+extern crate sdl2;
+use sdl2::rect::Rect;
+
+extern crate life;
+use life::automaton::{self, Automaton, Bounds};
+use life::format;
+use life::gui::{BoardRect, BoardRenderer};
+use life::image;
+use life::ndgame;
+use life::options;
+use life::sparse;
+
+use std::cmp;
+use std::env;
+use std::path::Path;
+use std::process;
+
+fn main() {
+  let args: Vec<String> = env::args().collect();
+  let mut opts = Options::new();
+  options::add_rule(&mut opts);
+  opts.optopt("a", "advance", "run this many generations before rendering (default 0)", "N");
+  opts.optopt("n", "generations", "generations to put in a GIF (default 100)", "N");
+  opts.optopt("d", "delay", "hundredths of a second to show each GIF frame for (default 10)", "N");
+  opts.optopt("s", "size", "width and height of the image in pixels (default 640)", "N");
+  let matches = match opts.parse(&args[1..]) {
+    Ok(m) => m,
+    Err(e) => { println!("{}", e); process::exit(1); }
+  };
+  if matches.free.len() != 2 {
+    print!("{}", opts.usage(&format!("Usage: {} [options] PATTERN OUTPUT.png|OUTPUT.gif", args[0])));
+    process::exit(1);
+  }
+  let rule = options::rule(&matches);
+  let advance: u64 = options::number(&matches, "advance", 0);
+  let size: u32 = options::number(&matches, "size", 640);
+  let delay: u16 = options::number(&matches, "delay", 10);
+  let output = Path::new(&matches.free[1]);
+  let gif = output.extension().map_or(false, |ext| ext.to_string_lossy().to_lowercase() == "gif");
+  let generations: u32 = if gif { options::number(&matches, "generations", 100) } else { 1 };
+
+  let mut pattern = match format::load(Path::new(&matches.free[0])) {
+    Ok(pattern) => pattern,
+    Err(e) => { println!("{}", e); process::exit(1); }
+  };
+  let rule = rule.unwrap_or(pattern.rule.unwrap_or_default());
+  pattern.rule = Some(rule);
+
+  let result = if !rule.is_life_like() || rule.born(0) {
+    // Only the ndarray board has decay states and larger neighborhoods, and being bounded, it can
+    // also run rules that give birth on zero neighbors. Leave room for the pattern to grow by as
+    // far as its neighborhood reaches every generation.
+    let reach = rule.neighborhood().range() as usize;
+    let margin = 2 * reach * (advance as usize + generations as usize);
+    let board = ndgame::Board::from_pattern(&pattern, pattern.height + margin, pattern.width + margin);
+    render(board, advance, generations, size, delay, output, gif)
+  } else {
+    let board = sparse::Board::from_board(&pattern.to_board());
+    render(board, advance, generations, size, delay, output, gif)
+  };
+  if let Err(e) = result {
+    println!("{}", e);
+    process::exit(1);
+  }
+}
+
+fn render<A: Automaton + Clone>(mut board: A, advance: u64, generations: u32, size: u32, delay: u16,
+                                output: &Path, gif: bool) -> Result<(), String> {
+  for _ in 0..advance {
+    board.step();
+  }
+
+  // Frame every generation being rendered, with a one cell margin.
+  let mut bounds = Bounds::new(0, 0, 0, 0);
+  let mut run = board.clone();
+  for _ in 0..generations {
+    if let Some(live) = automaton::live_bounds(&run) {
+      bounds = automaton::union(bounds, live);
+    }
+    run.step();
+  }
+  let side = cmp::max(bounds.rows, bounds.cols) + 2;
+  let view = BoardRect::new(bounds.r - (side - bounds.rows) as i64 / 2,
+                            bounds.c - (side - bounds.cols) as i64 / 2, side, side);
+  let board_renderer = BoardRenderer::new(Rect::new(0, 0, size, size)).with_board_rect(view);
+
+  if gif {
+    image::save_gif(&board, &board_renderer, generations, delay, output)
+  } else {
+    image::save_png(&board, &board_renderer, output)
+  }
+}
@@ -0,0 +1,86 @@
+// Headless soup search: runs many random soups, takes a census of each once it settles, and logs
+// the interesting ones (methuselahs, rare objects, spaceships) as RLE files plus a JSON log.
+
+extern crate getopts;
+use getopts::Options;
+
+extern crate life;
+use life::census::{Catalogue, Census};
+use life::format;
+use life::options;
+use life::seed;
+use life::soup::Soup;
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::process;
+
+fn main() {
+  let args: Vec<String> = env::args().collect();
+  let mut opts = Options::new();
+  opts.optopt("s", "seed", "seed of the first soup (default: random)", "SEED");
+  opts.optopt("n", "count", "number of soups to run (default 1000)", "N");
+  options::add_rule(&mut opts);
+  opts.optopt("", "size", "width and height of each soup (default 16)", "N");
+  opts.optopt("", "density", "probability of each soup cell being alive (default 0.5)", "P");
+  opts.optopt("g", "max-generations", "give up on soups that haven't settled by then (default 20000)", "N");
+  opts.optopt("m", "methuselah", "log soups that take at least this long to settle (default 1000)", "N");
+  opts.optopt("o", "output", "directory to write notable soups to (default soups)", "DIR");
+  let matches = match opts.parse(&args[1..]) {
+    Ok(m) => m,
+    Err(e) => { println!("{}", e); process::exit(1); }
+  };
+  let rule = options::rule(&matches).unwrap_or_default();
+  options::require_life_like(rule);
+  let seed: u64 = options::number(&matches, "seed", seed::random_seed());
+  let count: u64 = options::number(&matches, "count", 1000);
+  let size: usize = options::number(&matches, "size", 16);
+  let density: f32 = options::number(&matches, "density", 0.5);
+  let max_generations: u64 = options::number(&matches, "max-generations", 20000);
+  let methuselah: u64 = options::number(&matches, "methuselah", 1000);
+  let output = matches.opt_str("output").unwrap_or("soups".to_string());
+
+  let output = Path::new(&output);
+  if let Err(e) = fs::create_dir_all(output) {
+    println!("{}: {}", output.display(), e);
+    process::exit(1);
+  }
+  let log_path = output.join("log.jsonl");
+  let mut log = match OpenOptions::new().create(true).append(true).open(&log_path) {
+    Ok(f) => f,
+    Err(e) => { println!("{}: {}", log_path.display(), e); process::exit(1); }
+  };
+
+  println!("Searching {} soups from seed {} in {}", count, seed, rule);
+  let catalogue = Catalogue::new(rule);
+  let mut totals = BTreeMap::new();
+  let mut notable = 0;
+  // Seeds wrap around rather than overflow, so any start and count are fine.
+  for seed in (0..count).map(|i| seed.wrapping_add(i)) {
+    let soup = Soup::run(seed, size, density, &catalogue, max_generations);
+    for (name, n) in soup.census.counts.iter() {
+      *totals.entry(name.clone()).or_insert(0) += *n;
+    }
+
+    let reasons = soup.notable(methuselah);
+    if reasons.is_empty() {
+      continue;
+    }
+    notable += 1;
+    println!("soup {}: {}", seed, reasons.join(", "));
+    let path = output.join(format!("{}.rle", seed));
+    if let Err(e) = format::save(&soup.to_pattern_file(), &path) {
+      println!("{}", e);
+    }
+    if let Err(e) = writeln!(log, "{}", soup.to_json(&reasons)) {
+      println!("{}: {}", log_path.display(), e);
+    }
+  }
+
+  println!("");
+  println!("{} of {} soups were notable; objects found:", notable, count);
+  println!("{}", Census { counts: totals });
+}
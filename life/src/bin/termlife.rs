@@ -0,0 +1,178 @@
+// Runs a board in the terminal, for when there is no display to open an SDL window on (over SSH,
+// say). Patterns run on an unbounded board; without one, a random board fills the terminal.
+
+extern crate getopts;
+use getopts::Options;
+
+extern crate termion;
+use termion::event::Key;
+use termion::input::TermRead;
+use termion::raw::IntoRawMode;
+use termion::screen::AlternateScreen;
+use termion::{clear, cursor, style};
+
+extern crate life;
+use life::automaton::{self, Automaton, Bounds};
+use life::format;
+use life::game;
+use life::ndgame;
+use life::options;
+use life::seed;
+use life::sparse;
+use life::term::{self, Style};
+
+use std::cmp;
+use std::env;
+use std::io::{self, Write};
+use std::path::Path;
+use std::process;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const DENSITY: f32 = 0.47;
+
+const DEFAULT_DELAY: u64 = 100;
+const MIN_DELAY: u64 = 1;
+const MAX_DELAY: u64 = 2000;
+
+const HELP: &'static str = "space: pause  n: step  +/-: speed  arrows: pan  c: center  r: reset  \
+                            q: quit";
+
+fn main() {
+  let args: Vec<String> = env::args().collect();
+  let mut opts = Options::new();
+  options::add_rule(&mut opts);
+  opts.optopt("s", "seed", "seed for the random board (default: random)", "SEED");
+  opts.optopt("", "style", "ascii, half (two cells per character, the default) or braille (eight)", "STYLE");
+  let matches = match opts.parse(&args[1..]) {
+    Ok(m) => m,
+    Err(e) => { println!("{}", e); process::exit(1); }
+  };
+  let rule = options::rule(&matches);
+  let style: Style = match matches.opt_str("style").map(|s| s.parse()) {
+    Some(Ok(style)) => style,
+    Some(Err(e)) => { println!("{}", e); process::exit(1); }
+    None => Style::HalfBlock,
+  };
+  let seed: u64 = options::number(&matches, "seed", seed::random_seed());
+
+  let (cols, rows) = match termion::terminal_size() {
+    Ok((cols, rows)) if cols > 0 && rows > 1 => (cols as usize, rows as usize),
+    _ => (80, 24),
+  };
+  let (char_rows, char_cols) = style.cells_per_char();
+  // The bottom line is for the status bar.
+  let screen = ((rows - 1) * char_rows, cols * char_cols);
+
+  let result = match matches.free.first() {
+    Some(path) => {
+      let mut pattern = match format::load(Path::new(path)) {
+        Ok(pattern) => pattern,
+        Err(e) => { println!("{}", e); process::exit(1); }
+      };
+      let rule = rule.or(pattern.rule).unwrap_or_default();
+      pattern.rule = Some(rule);
+      if !rule.is_life_like() || rule.born(0) {
+        // The sparse board only runs Life-like rules without B0. The ndarray board is bounded, so it starts
+        // out the size of the screen.
+        run(ndgame::Board::from_pattern(&pattern, screen.0, screen.1), screen, style)
+      } else {
+        run(sparse::Board::from_board(&pattern.to_board()), screen, style)
+      }
+    }
+    None => {
+      let rule = rule.unwrap_or_default();
+      let mut rng = seed::seeded_rng(seed);
+      if rule.states() > 2 {
+        run(ndgame::Board::random(screen.0, screen.1, DENSITY, &mut rng).with_rule(rule), screen,
+            style)
+      } else {
+        run(game::Board::random(screen.0, screen.1, DENSITY, &mut rng).with_rule(rule), screen, style)
+      }
+    }
+  };
+  if let Err(e) = result {
+    println!("{}", e);
+    process::exit(1);
+  }
+  if matches.free.is_empty() {
+    println!("Seed: {}", seed);
+  }
+}
+
+// Animates `board` in a `screen` (rows, cols) cell view until the user quits.
+fn run<A: Automaton + Clone>(board: A, screen: (usize, usize), style: Style) -> io::Result<()> {
+  let start = board.clone();
+  let mut board = board;
+  let mut keys = termion::async_stdin().keys();
+  // Hides the cursor until `out` is dropped, so it comes back however this returns.
+  let out = AlternateScreen::from(try!(io::stdout().into_raw_mode()));
+  let mut out = cursor::HideCursor::from(out);
+
+  // Start with the live cells in the middle of the screen.
+  let centered = |board: &A| {
+    let live = automaton::live_bounds(board).unwrap_or(board.bounds());
+    Bounds::new(live.r + live.rows as i64 / 2 - screen.0 as i64 / 2,
+                live.c + live.cols as i64 / 2 - screen.1 as i64 / 2, screen.0, screen.1)
+  };
+  let mut view = centered(&board);
+  // The status bar is cut off at the width of the terminal, and shows the keys instead of the
+  // board's state while help is on.
+  let width = screen.1 / style.cells_per_char().1;
+  let mut help = false;
+  let mut paused = false;
+  let mut delay = DEFAULT_DELAY;
+  let mut last_step = Instant::now();
+
+  try!(write!(out, "{}", clear::All));
+  loop {
+    try!(write!(out, "{}", cursor::Goto(1, 1)));
+    for line in term::render(&board, view, style) {
+      try!(write!(out, "{}{}\r\n", line, clear::UntilNewline));
+    }
+    let status = if help {
+      HELP.to_string()
+    } else {
+      format!("generation {}  population {}  {}  {}ms  h: help", board.generation(),
+              board.population(), if paused { "paused" } else { "running" }, delay)
+    };
+    let status: String = status.chars().take(width).collect();
+    try!(write!(out, "{}{}{}{}", style::Invert, status, clear::UntilNewline, style::Reset));
+    try!(out.flush());
+
+    // Wait for a key or the next generation, and redraw after either.
+    loop {
+      if let Some(key) = keys.next() {
+        let (pan_r, pan_c) = ((view.rows / 8) as i64 + 1, (view.cols / 8) as i64 + 1);
+        match try!(key) {
+          Key::Char('q') | Key::Esc | Key::Ctrl('c') => { return Ok(()); }
+          Key::Char(' ') => { paused = !paused; }
+          Key::Char('h') | Key::Char('?') => { help = !help; }
+          Key::Char('n') => {
+            paused = true;
+            board.step();
+          }
+          Key::Char('+') | Key::Char('=') => { delay = cmp::max(MIN_DELAY, delay / 2); }
+          Key::Char('-') => { delay = cmp::min(MAX_DELAY, delay * 2); }
+          Key::Char('r') => { board = start.clone(); }
+          Key::Char('c') => { view = centered(&board); }
+          Key::Left => { view.c -= pan_c; }
+          Key::Right => { view.c += pan_c; }
+          Key::Up => { view.r -= pan_r; }
+          Key::Down => { view.r += pan_r; }
+          _ => ()
+        }
+        break;
+      }
+
+      let elapsed = last_step.elapsed();
+      let elapsed = elapsed.as_secs() * 1000 + (elapsed.subsec_nanos() / 1000000) as u64;
+      if !paused && elapsed >= delay {
+        board.step();
+        last_step = Instant::now();
+        break;
+      }
+      thread::sleep(Duration::from_millis(cmp::min(10, delay)));
+    }
+  }
+}
@@ -1,11 +1,22 @@
+extern crate getopts;
+use getopts::Options;
+
 extern crate life;
-use life::pattern_finder::Pattern;
+use life::format::{self, Format, PatternFile};
+use life::game::Board;
+use life::json;
+use life::pattern_finder::{Objective, Pattern, PatternSearch, Symmetry};
 use life::gui::BoardRenderer;
+use life::options;
+use life::seed;
 
 extern crate optimizer;
 use optimizer::Cost;
 use optimizer::annealing::{Annealer, Schedule};
 
+extern crate rand;
+use rand::Rng;
+
 extern crate sdl2;
 use sdl2::pixels::Color;
 use sdl2::rect::Rect;
@@ -13,6 +24,15 @@ use sdl2::render::Renderer;
 use sdl2::EventPump;
 use sdl2::event::Event;
 
+use std::env;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::process;
+
+const BOARD_SIZE: usize = 100;
+const INITIAL_TEMP: f64 = 0.00125;
+
 /*
 fn mean(arr: &[f64]) -> f64 {
   arr.iter().sum::<f64>() / arr.len() as f64
@@ -26,7 +46,7 @@ fn stddev(arr: &[f64], mu: f64) -> f64 {
 struct BasicSchedule;
 impl Schedule for BasicSchedule {
   fn temp(step: u32, _step_max: u32) -> f64 {
-    0.00125 / (1. + (1. + step as f64).ln())
+    INITIAL_TEMP / (1. + (1. + step as f64).ln())
   }
 }
 
@@ -43,24 +63,54 @@ fn visit_cb(p: &Pattern, board_renderer: &BoardRenderer, mut renderer: &mut Rend
   renderer.present();
 }
 
-fn train(start: Pattern, mut renderer: &mut Renderer, mut event_pump: &mut EventPump) -> Pattern {
-  let board_renderer = BoardRenderer::new(Rect::new(0, 0, 640, 640)).with_board_rect(life::gui::BoardRect::new(/*95,95*/45,45,10,10));
-
-  let cb = |p: &Pattern| visit_cb(&p, &board_renderer, &mut renderer, &mut event_pump);
-
+// Anneals from `start` for `steps` steps, calling `cb` on every state visited, and returns the best
+// pattern found along with its cost.
+fn train<'a, R: Rng>(start: Pattern, steps: u32, cb: Option<Box<FnMut(&Pattern) + 'a>>, rng: &mut R) -> (Pattern, f64) {
   let start_cost = start.cost();
   println!("Start cost: {}", start_cost);
 
   let mut annealer = Annealer::<Pattern>::new(start);
-  annealer.set_visit_cb(Box::new(cb));
+  if let Some(cb) = cb {
+    annealer.set_visit_cb(cb);
+  }
 
-  let final_state = annealer.optimize::<BasicSchedule>(7000);
-  let end_cost = final_state.cost();
+  annealer.optimize::<BasicSchedule, _>(steps, rng);
+  let (best, end_cost) = annealer.best();
   println!("Start cost:  {}", start_cost);
   println!("End cost:    {}", end_cost);
   println!("Improvement: {}", -(end_cost-start_cost));
 
-  final_state.clone()
+  (best.clone(), end_cost)
+}
+
+// Like `train`, but shows each candidate in the window as it is visited.
+fn train_in_window<R: Rng>(start: Pattern, steps: u32, rng: &mut R, mut renderer: &mut Renderer, mut event_pump: &mut EventPump) -> (Pattern, f64) {
+  let size = start.search().candidate_size;
+  let corner = (BOARD_SIZE / 2 - size / 2) as i64;
+  let board_renderer = BoardRenderer::new(Rect::new(0, 0, 640, 640)).with_board_rect(life::gui::BoardRect::new(corner, corner, size, size));
+
+  let cb = |p: &Pattern| visit_cb(&p, &board_renderer, &mut renderer, &mut event_pump);
+  train(start, steps, Some(Box::new(cb)), rng)
+}
+
+// Saves the pattern in the format its extension names, plus a JSON sidecar recording its cost and
+// how the search that found it was set up, including the seed to replay it with. The sidecar is
+// named after the pattern file with `.json` added, so `best.rle` gets `best.rle.json`.
+fn save(pattern: &Pattern, cost: f64, seed: u64, steps: u32, path: &Path) -> Result<(), String> {
+  let mut file = pattern.to_pattern_file();
+  file.comments.push(format!("Found by train with cost {}", cost));
+  try!(format::save(&file, path));
+
+  let mut sidecar = path.file_name().unwrap_or_default().to_os_string();
+  sidecar.push(".json");
+  let sidecar = path.with_file_name(sidecar);
+  let json = format!("{{\n  \"pattern\": {},\n  \"rule\": {},\n  \"cost\": {},\n  \"seed\": {},\n  \
+                     \"schedule\": {{\"initial_temp\": {}, \"steps\": {}}},\n  \"search\": {}\n}}\n",
+                     json::string(&path.file_name().unwrap_or_default().to_string_lossy()),
+                     json::string(&pattern.starting_board().rule().to_string()), json::number(cost),
+                     seed, json::number(INITIAL_TEMP), steps, pattern.search().to_json());
+  File::create(&sidecar).and_then(|mut f| f.write_all(json.as_bytes()))
+    .map_err(|e| format!("{}: {}", sidecar.display(), e))
 }
 
 fn present(pattern: &Pattern, steps: u32, mut renderer: &mut Renderer, event_pump: &mut EventPump) {
@@ -86,6 +136,95 @@ fn present(pattern: &Pattern, steps: u32, mut renderer: &mut Renderer, event_pum
 }
 
 fn main() {
+  let args: Vec<String> = env::args().collect();
+  let mut opts = Options::new();
+  options::add_rule(&mut opts);
+  opts.optopt("", "size", "width and height of candidate patterns (default 10)", "N");
+  opts.optopt("", "symmetry", "symmetry of candidate patterns: C1 (the default), C2, C4, D2, D4 or D8", "SYMMETRY");
+  opts.optopt("", "flips", "cells flipped to get from one candidate to the next (default 1)", "N");
+  opts.optopt("g", "generations", "how long to run candidates for (default 1000)", "N");
+  opts.optopt("", "steps", "number of annealing steps (default 7000)", "N");
+  opts.optopt("", "output", "where to save the best pattern found (default best.rle)", "FILE");
+  opts.optflag("", "headless", "search without opening a window");
+  opts.optopt("s", "seed", "seed for the search (default: random)", "SEED");
+  opts.optmulti("o", "objective", "what to search for: activity (the default), longevity, population, \
+                                   period, displacement or growth, with an optional weight", "NAME[:WEIGHT]");
+  let matches = match opts.parse(&args[1..]) {
+    Ok(m) => m,
+    Err(e) => { println!("{}", e); process::exit(1); }
+  };
+  let rule = options::rule(&matches).unwrap_or_default();
+  options::require_two_states(rule);
+
+  let mut search = PatternSearch::new();
+  if let Some(size) = matches.opt_str("size") {
+    match size.parse() {
+      Ok(size) if size > 0 && size <= BOARD_SIZE => search = search.with_candidate_size(size),
+      _ => { println!("--size must be a number from 1 to {}", BOARD_SIZE); process::exit(1); }
+    }
+  }
+  if let Some(symmetry) = matches.opt_str("symmetry") {
+    match symmetry.parse::<Symmetry>() {
+      Ok(symmetry) => search = search.with_symmetry(symmetry),
+      Err(e) => { println!("{}", e); process::exit(1); }
+    }
+  }
+  if let Some(flips) = matches.opt_str("flips") {
+    match flips.parse() {
+      Ok(flips) => search = search.with_flips(flips),
+      Err(_) => { println!("--flips must be a number"); process::exit(1); }
+    }
+  }
+  if let Some(generations) = matches.opt_str("generations") {
+    match generations.parse() {
+      Ok(generations) => search = search.with_generations(generations),
+      Err(_) => { println!("--generations must be a number"); process::exit(1); }
+    }
+  }
+  for objective in matches.opt_strs("objective") {
+    let mut parts = objective.splitn(2, ':');
+    let name = parts.next().unwrap();
+    let weight = match parts.next().map(|w| w.parse::<f64>()) {
+      Some(Ok(weight)) => weight,
+      Some(Err(_)) => { println!("bad weight in objective {:?}", objective); process::exit(1); }
+      None => 1.,
+    };
+    match name.parse::<Objective>() {
+      Ok(objective) => search = search.with_objective(objective, weight),
+      Err(e) => { println!("{}", e); process::exit(1); }
+    }
+  }
+
+  let steps: u32 = options::number(&matches, "steps", 7000);
+  let output = matches.opt_str("output").unwrap_or("best.rle".to_string());
+  // Check that patterns in this rule can be saved there before searching, rather than after.
+  let empty = PatternFile { rule: Some(rule), ..Default::default() };
+  let checked = Format::from_extension(Path::new(&output)).and_then(|format| {
+    format::write(&empty, format).map_err(|e| format!("{}: {}", output, e))
+  });
+  if let Err(e) = checked {
+    println!("{}", e);
+    process::exit(1);
+  }
+  let seed: u64 = options::number(&matches, "seed", seed::random_seed());
+  println!("Seed: {}", seed);
+  let mut rng = seed::seeded_rng(seed);
+
+  let start_state = Pattern::random(Board::new(BOARD_SIZE, BOARD_SIZE).with_rule(rule), search, &mut rng);
+  if matches.opt_present("headless") {
+    let (best, cost) = train(start_state, steps, None, &mut rng);
+    if let Err(e) = save(&best, cost, seed, steps, Path::new(&output)) {
+      println!("{}", e);
+      process::exit(1);
+    }
+  } else {
+    search_in_window(start_state, steps, seed, Path::new(&output), &mut rng);
+  }
+}
+
+// Shows the starting pattern, the search and then the best pattern found in a window. SDL is only
+// initialized here, so --headless searches run where there is no display.
+fn search_in_window<R: Rng>(start: Pattern, steps: u32, seed: u64, output: &Path, rng: &mut R) {
   let sdl_context = sdl2::init().unwrap();
   let video_ctx = sdl_context.video().unwrap();
 
@@ -93,8 +232,10 @@ fn main() {
   let mut renderer = window.renderer().present_vsync().build().unwrap();
   let mut event_pump = sdl_context.event_pump().unwrap();
 
-  let start_state = Pattern::random();
-  present(&start_state, 200, &mut renderer, &mut event_pump);
-  let final_state = train(start_state, &mut renderer, &mut event_pump);
-  present(&final_state, 100000, &mut renderer, &mut event_pump);
+  present(&start, 200, &mut renderer, &mut event_pump);
+  let (best, cost) = train_in_window(start, steps, rng, &mut renderer, &mut event_pump);
+  if let Err(e) = save(&best, cost, seed, steps, output) {
+    println!("{}", e);
+  }
+  present(&best, 100000, &mut renderer, &mut event_pump);
 }
@@ -0,0 +1,209 @@
+// A bit-packed board: each row is stored as u64 words holding 64 cells apiece, and a generation is
+// computed a whole word at a time by counting neighbors with bitwise adder logic.
+//
+// Like `ndgame::Board`, the board is surrounded by a one cell padding border that is refilled
+// from the topology before every step, so the word operations never need to special-case edges.
+
+use std::mem;
+
+use automaton::{Automaton, Bounds};
+use game;
+use rule::Rule;
+use topology::Topology;
+
+#[derive(Clone, Debug)]
+pub struct Board {
+  // (rows + 2) rows of `words` words; bit `c % 64` of word `c / 64` holds padded column `c`.
+  bits: Vec<u64>,
+  scratch: Vec<u64>,
+  rows: usize,
+  cols: usize,
+  words: usize,
+  topology: Topology,
+  rule: Rule,
+  pub generation: u64,
+}
+
+// Adds three one-bit numbers in every bit position, returning (sum, carry).
+fn full_add(a: u64, b: u64, c: u64) -> (u64, u64) {
+  let ab = a ^ b;
+  (ab ^ c, (a & b) | (c & ab))
+}
+
+impl Board {
+  pub fn new(rows: usize, cols: usize) -> Board {
+    let words = (cols + 2 + 63) / 64;
+    Board {
+      bits: vec![0; (rows + 2) * words],
+      scratch: vec![0; (rows + 2) * words],
+      rows: rows,
+      cols: cols,
+      words: words,
+      topology: Topology::Bounded,
+      rule: Rule::conway(),
+      generation: 0,
+    }
+  }
+
+  pub fn with_topology(mut self, topology: Topology) -> Board {
+    self.topology = topology;
+    self
+  }
+
+  pub fn with_rule(mut self, rule: Rule) -> Board {
+    assert!(rule.is_life_like(), "bitgame::Board only runs Life-like rules, not {}", rule);
+    self.rule = rule;
+    self
+  }
+
+  pub fn from_board(board: &game::Board) -> Board {
+    let (rows, cols) = board.size();
+    let mut bits = Board::new(rows, cols).with_topology(board.topology()).with_rule(board.rule());
+    for r in 0..rows {
+      for c in 0..cols {
+        if board[r][c] {
+          bits.set_padded(r + 1, c + 1, true);
+        }
+      }
+    }
+    bits.generation = board.generation;
+    bits
+  }
+
+  pub fn parse(x: &[u8]) -> Board {
+    Board::from_board(&game::Board::parse(x))
+  }
+
+  pub fn size(&self) -> (usize, usize) {
+    (self.rows, self.cols)
+  }
+
+  fn get_padded(&self, r: usize, c: usize) -> bool {
+    (self.bits[r * self.words + c / 64] >> (c % 64)) & 1 == 1
+  }
+
+  fn set_padded(&mut self, r: usize, c: usize, alive: bool) {
+    let word = &mut self.bits[r * self.words + c / 64];
+    if alive {
+      *word |= 1 << (c % 64);
+    } else {
+      *word &= !(1 << (c % 64));
+    }
+  }
+
+  // Copies into the padding border the cells it stands for under this board's topology.
+  fn fill_border(&mut self) {
+    let (n, m) = (self.rows + 2, self.cols + 2);
+    for c in 0..m {
+      self.fill_border_cell(0, c);
+      self.fill_border_cell(n - 1, c);
+    }
+    for r in 1..n - 1 {
+      self.fill_border_cell(r, 0);
+      self.fill_border_cell(r, m - 1);
+    }
+  }
+
+  fn fill_border_cell(&mut self, r: usize, c: usize) {
+    let alive = match self.topology.wrap(r as isize - 1, c as isize - 1, self.rows, self.cols) {
+      Some((r, c)) => self.get_padded(r + 1, c + 1),
+      None => false,
+    };
+    self.set_padded(r, c, alive);
+  }
+
+  pub fn iterate(&mut self) {
+    self.fill_border();
+
+    let words = self.words;
+    let born: Vec<u8> = (0..9).filter(|&n| self.rule.born(n)).collect();
+    let survive: Vec<u8> = (0..9).filter(|&n| self.rule.survives(n)).collect();
+
+    // Only the interior columns of each row are written back.
+    let mut mask = vec![!0u64; words];
+    mask[0] &= !1;
+    for c in self.cols + 1..words * 64 {
+      mask[c / 64] &= !(1 << (c % 64));
+    }
+
+    for w in 0..words {
+      self.scratch[w] = 0;
+      self.scratch[(self.rows + 1) * words + w] = 0;
+    }
+
+    for r in 1..self.rows + 1 {
+      for w in 0..words {
+        // The row's word shifted so each bit lines up with its west, center and east neighbors.
+        let shifted = |row: usize| {
+          let i = row * words + w;
+          let x = self.bits[i];
+          let before = if w > 0 { self.bits[i - 1] } else { 0 };
+          let after = if w + 1 < words { self.bits[i + 1] } else { 0 };
+          ((x << 1) | (before >> 63), x, (x >> 1) | (after << 63))
+        };
+        let (nw, n, ne) = shifted(r - 1);
+        let (west, center, east) = shifted(r);
+        let (sw, s, se) = shifted(r + 1);
+
+        // Sum the eight neighbor bits into a four bit count (bit0..bit3) in every position.
+        let (ones_a, twos_a) = full_add(nw, n, ne);
+        let (ones_b, twos_b) = full_add(west, east, sw);
+        let (ones_c, twos_c) = (s ^ se, s & se);
+        let (bit0, twos_d) = full_add(ones_a, ones_b, ones_c);
+        let (twos, fours_a) = full_add(twos_a, twos_b, twos_c);
+        let (bit1, fours_b) = (twos ^ twos_d, twos & twos_d);
+        let (bit2, bit3) = (fours_a ^ fours_b, fours_a & fours_b);
+
+        let count_is = |count: u8| {
+          let bit = |i: u8, word: u64| if (count >> i) & 1 == 1 { word } else { !word };
+          bit(0, bit0) & bit(1, bit1) & bit(2, bit2) & bit(3, bit3)
+        };
+        let born = born.iter().fold(0, |acc, &count| acc | count_is(count));
+        let survive = survive.iter().fold(0, |acc, &count| acc | count_is(count));
+
+        self.scratch[r * words + w] = ((!center & born) | (center & survive)) & mask[w];
+      }
+    }
+
+    mem::swap(&mut self.bits, &mut self.scratch);
+    self.generation += 1;
+  }
+}
+
+impl Automaton for Board {
+  fn get(&self, r: i64, c: i64) -> bool {
+    r >= 0 && (r as usize) < self.rows && c >= 0 && (c as usize) < self.cols &&
+      self.get_padded(r as usize + 1, c as usize + 1)
+  }
+
+  fn set(&mut self, r: i64, c: i64, alive: bool) {
+    assert!(r >= 0 && (r as usize) < self.rows && c >= 0 && (c as usize) < self.cols,
+            "cell ({}, {}) is outside the board", r, c);
+    self.set_padded(r as usize + 1, c as usize + 1, alive);
+  }
+
+  fn step(&mut self) {
+    self.iterate();
+  }
+
+  fn population(&self) -> u64 {
+    // The padding border is only filled in during `iterate`, so every set bit is a live cell.
+    self.bits.iter().map(|word| word.count_ones() as u64).sum()
+  }
+
+  fn generation(&self) -> u64 {
+    self.generation
+  }
+
+  fn set_generation(&mut self, generation: u64) {
+    self.generation = generation;
+  }
+
+  fn bounds(&self) -> Bounds {
+    Bounds::new(0, 0, self.rows, self.cols)
+  }
+
+  fn rule(&self) -> Rule {
+    self.rule
+  }
+}
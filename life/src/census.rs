@@ -0,0 +1,213 @@
+// An apgsearch-style census of a settled board: the live cells are split into separate objects,
+// each object is put into a canonical orientation, and it is named by looking it up in a built-in
+// catalogue of common still lifes, oscillators and spaceships.
+
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::fmt;
+
+use automaton::Automaton;
+use cycle::{self, Kind};
+use format::rle;
+use rule::Rule;
+use sparse;
+
+// Live cells closer than this (in either direction) belong to the same object. Cells two apart
+// still interact, so objects are only considered separate when there is a gap of two empty cells.
+const OBJECT_DISTANCE: i64 = 2;
+
+// How many generations ahead `objects` looks when joining cells into objects.
+const LINK_GENERATIONS: u64 = 4;
+
+// Unknown objects are run for this long to find out what they are.
+const MAX_GENERATIONS: u64 = 1000;
+
+const CATALOGUE: &'static [(&'static str, &'static str)] = &[
+  ("block", "2o$2o!"),
+  ("beehive", "b2o$o2bo$b2o!"),
+  ("loaf", "b2o$o2bo$bobo$2bo!"),
+  ("boat", "2o$obo$bo!"),
+  ("ship", "2o$obo$b2o!"),
+  ("tub", "bo$obo$bo!"),
+  ("pond", "b2o$o2bo$o2bo$b2o!"),
+  ("long boat", "2o$obo$bobo$2bo!"),
+  ("barge", "bo$obo$bobo$2bo!"),
+  ("mango", "b2o$o2bo$bo2bo$2b2o!"),
+  ("eater 1", "2o$obo$2bo$2b2o!"),
+  ("snake", "2obo$ob2o!"),
+  ("aircraft carrier", "2o$o2bo$2b2o!"),
+  ("blinker", "3o!"),
+  ("toad", "b3o$3o!"),
+  ("beacon", "2o$o$3bo$2b2o!"),
+  ("traffic light", "2b3o2$o5bo$o5bo$o5bo2$2b3o!"),
+  ("pulsar", "2b3o3b3o2$o4bobo4bo$o4bobo4bo$o4bobo4bo$2b3o3b3o2$2b3o3b3o$o4bobo4bo$o4bobo4bo$o4bobo4bo2$2b3o3b3o!"),
+  ("pentadecathlon", "2bo4bo$2ob4ob2o$2bo4bo!"),
+  ("glider", "bo$2bo$3o!"),
+  ("lightweight spaceship", "bo2bo$o$o3bo$4o!"),
+  ("middleweight spaceship", "3bo$bo3bo$o$o4bo$5o!"),
+  ("heavyweight spaceship", "3b2o$bo4bo$o$o5bo$6o!"),
+];
+
+type Shape = Vec<(i64, i64)>;
+
+// The smallest of the eight rotations and reflections of `cells`, each shifted so its bounding box
+// starts at (0, 0), so that every orientation of an object has the same shape.
+pub fn canonical_shape(cells: &[(i64, i64)]) -> Shape {
+  let transforms: [fn(i64, i64) -> (i64, i64); 8] = [
+    |r, c| (r, c), |r, c| (r, -c), |r, c| (-r, c), |r, c| (-r, -c),
+    |r, c| (c, r), |r, c| (c, -r), |r, c| (-c, r), |r, c| (-c, -r),
+  ];
+  transforms.iter().map(|transform| {
+    let mut shape: Shape = cells.iter().map(|&(r, c)| transform(r, c)).collect();
+    let r0 = shape.iter().map(|&(r, _)| r).min().unwrap_or(0);
+    let c0 = shape.iter().map(|&(_, c)| c).min().unwrap_or(0);
+    for cell in shape.iter_mut() {
+      *cell = (cell.0 - r0, cell.1 - c0);
+    }
+    shape.sort();
+    shape
+  }).min().unwrap()
+}
+
+// Splits the live cells of `board` into objects, each a list of cells. Some oscillators, like the
+// pentadecathlon, fall apart into pieces in some phases, so the next few generations are looked at
+// too: cells are joined when they are close in the same generation, or when one is a neighbor of
+// a cell in the generation before, which it could have come from.
+pub fn objects<A: Automaton + Clone>(board: &A) -> Vec<Vec<(i64, i64)>> {
+  let mut generations = vec![board.live_cells().into_iter().collect::<HashSet<_>>()];
+  let mut future = board.clone();
+  for _ in 0..LINK_GENERATIONS {
+    future.step();
+    generations.push(future.live_cells().into_iter().collect());
+  }
+  let mut remaining = generations.clone();
+  let mut objects = Vec::new();
+
+  while let Some(&start) = generations[0].iter().find(|cell| remaining[0].contains(cell)) {
+    remaining[0].remove(&start);
+    let mut object = vec![start];
+    let mut queue = VecDeque::new();
+    queue.push_back((0, start));
+    while let Some((g, (r, c))) = queue.pop_front() {
+      for dr in -OBJECT_DISTANCE..OBJECT_DISTANCE + 1 {
+        for dc in -OBJECT_DISTANCE..OBJECT_DISTANCE + 1 {
+          let cell = (r + dr, c + dc);
+          let adjacent = dr.abs() <= 1 && dc.abs() <= 1;
+          let mut linked = vec![g];
+          if adjacent && g > 0 {
+            linked.push(g - 1);
+          }
+          if adjacent && g + 1 < generations.len() {
+            linked.push(g + 1);
+          }
+          for h in linked {
+            if remaining[h].remove(&cell) {
+              if h == 0 {
+                object.push(cell);
+              }
+              queue.push_back((h, cell));
+            }
+          }
+        }
+      }
+    }
+    object.sort();
+    objects.push(object);
+  }
+  objects
+}
+
+// Names objects by the canonical shape of every phase of every object in `CATALOGUE`. Building
+// one means running each of those objects, so searches that take many censuses should build it
+// once and pass it to each.
+pub struct Catalogue {
+  rule: Rule,
+  names: HashMap<Shape, &'static str>,
+}
+
+impl Catalogue {
+  // The catalogue is for Conway's Life, so other rules get an empty one and all their objects are
+  // named by `describe`. Objects are run on sparse boards, so the rule must be one they take.
+  pub fn new(rule: Rule) -> Catalogue {
+    assert!(rule.is_life_like() && !rule.born(0),
+            "a census can only be taken with Life-like rules without B0, not {}", rule);
+    let mut names = HashMap::new();
+    if rule == Rule::conway() {
+      for &(name, pattern) in CATALOGUE.iter() {
+        let pattern = rle::read(&format!("x = 0, y = 0\n{}", pattern)).unwrap();
+        let mut board = sparse::Board::from_board(&pattern.to_board());
+        let period = cycle::find_cycle(&board, MAX_GENERATIONS).map_or(1, |cycle| cycle.period);
+        for _ in 0..period {
+          names.insert(canonical_shape(&board.live_cells()), name);
+          board.step();
+        }
+      }
+    }
+    Catalogue { rule: rule, names: names }
+  }
+
+  pub fn rule(&self) -> Rule {
+    self.rule
+  }
+
+  // The name of the object made of `cells`.
+  pub fn name(&self, cells: &[(i64, i64)]) -> String {
+    match self.names.get(&canonical_shape(cells)) {
+      Some(name) => name.to_string(),
+      None => describe(cells, self.rule),
+    }
+  }
+}
+
+// Names objects that aren't in the catalogue after the apgcode prefixes: `xs` and the population
+// for still lifes, `xp` and the period for oscillators, `xq` and the period for spaceships.
+fn describe(cells: &[(i64, i64)], rule: Rule) -> String {
+  let mut board = sparse::Board::new().with_rule(rule);
+  for &(r, c) in cells.iter() {
+    board.set(r, c, true);
+  }
+  match cycle::find_cycle(&board, MAX_GENERATIONS) {
+    Some(cycle) => match cycle.kind() {
+      Kind::Died => "dies out".to_string(),
+      Kind::StillLife => format!("xs{}", cycle.population),
+      Kind::Oscillator => format!("xp{}", cycle.period),
+      Kind::Spaceship => format!("xq{}", cycle.period),
+    },
+    None => "unsettled".to_string(),
+  }
+}
+
+pub struct Census {
+  pub counts: BTreeMap<String, usize>,
+}
+
+impl Census {
+  // Takes a census of `board`, which should already have settled down, naming its objects from a
+  // catalogue for the board's rule.
+  pub fn take<A: Automaton + Clone>(board: &A, catalogue: &Catalogue) -> Census {
+    assert!(catalogue.rule() == board.rule(), "census of a {} board with a catalogue for {}",
+            board.rule(), catalogue.rule());
+    let mut counts = BTreeMap::new();
+    for object in objects(board) {
+      *counts.entry(catalogue.name(&object)).or_insert(0) += 1;
+    }
+    Census { counts: counts }
+  }
+
+  pub fn total(&self) -> usize {
+    self.counts.values().sum()
+  }
+}
+
+// Prints the census as a table, most common objects first.
+impl fmt::Display for Census {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let mut rows: Vec<(&String, &usize)> = self.counts.iter().collect();
+    rows.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+    let width = rows.iter().map(|&(name, _)| name.len()).max().unwrap_or(0);
+    for (name, count) in rows {
+      try!(writeln!(f, "{:width$}  {:>5}", name, count, width = width));
+    }
+    write!(f, "{:width$}  {:>5}", "total", self.total(), width = width)
+  }
+}
+
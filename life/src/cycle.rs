@@ -0,0 +1,88 @@
+// Detects when a board starts repeating itself, which tells still lifes, oscillators and
+// spaceships apart from patterns that died or haven't settled down yet.
+
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hasher;
+
+use automaton::{self, Automaton, Bounds};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+  Died,
+  StillLife,
+  Oscillator,
+  Spaceship,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cycle {
+  // Generations before the board first reaches a state it later returns to.
+  pub transient: u64,
+  pub period: u64,
+  // How far the live cells move each period, as (rows, cols).
+  pub displacement: (i64, i64),
+  // Population at the start of the cycle.
+  pub population: u64,
+}
+
+impl Cycle {
+  pub fn kind(&self) -> Kind {
+    if self.population == 0 {
+      Kind::Died
+    } else if self.displacement != (0, 0) {
+      Kind::Spaceship
+    } else if self.period == 1 {
+      Kind::StillLife
+    } else {
+      Kind::Oscillator
+    }
+  }
+}
+
+// Hashes the live cells of a board relative to their bounding box, so that a pattern hashes the
+// same wherever it is on the board. Also returns the bounding box, or None if the board is empty.
+pub fn pattern_hash<A: Automaton>(board: &A) -> (u64, Option<Bounds>) {
+  let mut hasher = DefaultHasher::new();
+  let cells = board.live_cells();
+  let bounds = automaton::bounds_of(&cells);
+  if let Some(bounds) = bounds {
+    hasher.write_usize(bounds.rows);
+    hasher.write_usize(bounds.cols);
+    for &(r, c) in cells.iter() {
+      hasher.write_i64(r - bounds.r);
+      hasher.write_i64(c - bounds.c);
+    }
+  }
+  (hasher.finish(), bounds)
+}
+
+// Steps a copy of `board` until it repeats a previous state (up to translation), giving up after
+// `max_generations`. States are compared by hash, so a collision could in principle report a
+// spurious cycle.
+pub fn find_cycle<A: Automaton + Clone>(board: &A, max_generations: u64) -> Option<Cycle> {
+  let mut board = board.clone();
+  let start = board.generation();
+  // Generation and bounding box corner at which each state was first seen.
+  let mut seen: HashMap<u64, (u64, i64, i64)> = HashMap::new();
+
+  loop {
+    let (hash, bounds) = pattern_hash(&board);
+    let generation = board.generation() - start;
+    let (r, c) = bounds.map_or((0, 0), |b| (b.r, b.c));
+    if let Some(&(first, r0, c0)) = seen.get(&hash) {
+      return Some(Cycle {
+        transient: first,
+        period: generation - first,
+        displacement: (r - r0, c - c0),
+        population: board.population(),
+      });
+    }
+    if generation >= max_generations {
+      return None;
+    }
+
+    seen.insert(hash, (generation, r, c));
+    board.step();
+  }
+}
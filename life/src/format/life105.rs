@@ -0,0 +1,109 @@
+// Life 1.05 patterns, as described at http://www.conwaylife.com/wiki/Life_1.05: a header line,
+// `#D` description lines, an optional `#N` (normal rules) or `#R` rule line, and blocks of `*`/`.`
+// cells each positioned by a `#P x y` line.
+//
+//   #Life 1.05
+//   #D Glider
+//   #N
+//   #P -1 -1
+//   .*.
+//   ..*
+//   ***
+
+use std::path::Path;
+
+use format::{self, PatternFile};
+use rule::Rule;
+
+pub const HEADER: &'static str = "#Life 1.05";
+
+pub fn read(input: &str) -> Result<PatternFile, String> {
+  let mut name = None;
+  let mut comments = Vec::new();
+  let mut rule = None;
+  let mut points = Vec::new();
+  let (mut block_x, mut block_y) = (0isize, 0isize);
+  let mut r = 0;
+
+  for line in input.lines() {
+    let line = line.trim();
+    if line.starts_with("#Life") {
+      continue;
+    } else if line.starts_with("#D") {
+      let text = line[2..].trim().to_string();
+      if name.is_none() {
+        name = Some(text);
+      } else {
+        comments.push(text);
+      }
+    } else if line.starts_with("#N") {
+      rule = Some(Rule::conway());
+    } else if line.starts_with("#R") {
+      rule = Some(try!(line[2..].trim().parse()));
+    } else if line.starts_with("#P") {
+      let coords: Vec<&str> = line[2..].split_whitespace().collect();
+      if coords.len() != 2 {
+        return Err(format!("expected '#P x y', found '{}'", line));
+      }
+      block_x = try!(coords[0].parse().map_err(|_| format!("invalid coordinate '{}'", coords[0])));
+      block_y = try!(coords[1].parse().map_err(|_| format!("invalid coordinate '{}'", coords[1])));
+      r = 0;
+    } else if line.starts_with('#') || line.is_empty() {
+      continue;
+    } else {
+      for (c, ch) in line.chars().enumerate() {
+        match ch {
+          '*' => points.push((block_y + r, block_x + c as isize)),
+          '.' => (),
+          ch => return Err(format!("unexpected character '{}' in pattern", ch)),
+        }
+      }
+      r += 1;
+    }
+  }
+
+  let mut pattern = PatternFile::from_points(&points);
+  pattern.name = name;
+  pattern.comments = comments;
+  pattern.rule = rule;
+  Ok(pattern)
+}
+
+pub fn write(pattern: &PatternFile) -> Result<String, String> {
+  try!(format::require_life_like(pattern, "Life 1.05"));
+  let mut out = String::new();
+  out.push_str(HEADER);
+  out.push('\n');
+  for line in pattern.name.iter().chain(pattern.comments.iter()) {
+    out.push_str(&format!("#D {}\n", line));
+  }
+  match pattern.rule {
+    Some(rule) if rule != Rule::conway() => {
+      // Life 1.05 uses the legacy survival/birth form of the rule.
+      let survival: String = (0..9).filter(|&n| rule.survives(n)).map(|n| n.to_string()).collect();
+      let birth: String = (0..9).filter(|&n| rule.born(n)).map(|n| n.to_string()).collect();
+      out.push_str(&format!("#R {}/{}\n", survival, birth));
+    }
+    _ => out.push_str("#N\n"),
+  }
+
+  out.push_str("#P 0 0\n");
+  let mut grid = vec![vec!['.'; pattern.width]; pattern.height];
+  for &(r, c) in pattern.cells.iter() {
+    grid[r][c] = '*';
+  }
+  for row in grid {
+    out.extend(row);
+    out.push('\n');
+  }
+  Ok(out)
+}
+
+pub fn load(path: &Path) -> Result<PatternFile, String> {
+  read(&try!(format::read_to_string(path)))
+}
+
+pub fn save(pattern: &PatternFile, path: &Path) -> Result<(), String> {
+  let output = try!(write(pattern).map_err(|e| format!("{}: {}", path.display(), e)));
+  format::write_string(path, &output)
+}
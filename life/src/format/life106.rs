@@ -0,0 +1,55 @@
+// Life 1.06 patterns, as described at http://www.conwaylife.com/wiki/Life_1.06: a header line
+// followed by one `x y` coordinate pair per live cell.
+//
+//   #Life 1.06
+//   0 -1
+//   1 0
+//   -1 1
+//   0 1
+//   1 1
+
+use std::path::Path;
+
+use format::{self, PatternFile};
+
+pub const HEADER: &'static str = "#Life 1.06";
+
+pub fn read(input: &str) -> Result<PatternFile, String> {
+  let mut points = Vec::new();
+  for line in input.lines() {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+      continue;
+    }
+
+    let coords: Vec<&str> = line.split_whitespace().collect();
+    if coords.len() != 2 {
+      return Err(format!("expected 'x y' coordinates, found '{}'", line));
+    }
+    let x: isize = try!(coords[0].parse().map_err(|_| format!("invalid coordinate '{}'", coords[0])));
+    let y: isize = try!(coords[1].parse().map_err(|_| format!("invalid coordinate '{}'", coords[1])));
+    points.push((y, x));
+  }
+
+  Ok(PatternFile::from_points(&points))
+}
+
+pub fn write(pattern: &PatternFile) -> Result<String, String> {
+  try!(format::require_conway(pattern, "Life 1.06"));
+  let mut out = String::new();
+  out.push_str(HEADER);
+  out.push('\n');
+  for &(r, c) in pattern.cells.iter() {
+    out.push_str(&format!("{} {}\n", c, r));
+  }
+  Ok(out)
+}
+
+pub fn load(path: &Path) -> Result<PatternFile, String> {
+  read(&try!(format::read_to_string(path)))
+}
+
+pub fn save(pattern: &PatternFile, path: &Path) -> Result<(), String> {
+  let output = try!(write(pattern).map_err(|e| format!("{}: {}", path.display(), e)));
+  format::write_string(path, &output)
+}
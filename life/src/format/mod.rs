@@ -0,0 +1,240 @@
+// Readers and writers for the pattern file formats used by other Life software (LifeWiki, Golly).
+
+use std::cmp;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+
+use game::Board;
+use rule::Rule;
+
+pub mod life105;
+pub mod life106;
+pub mod plaintext;
+pub mod rle;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+  // The `#`/`.` grid read by `game::Board::parse`.
+  Grid,
+  Rle,
+  Plaintext,
+  Life105,
+  Life106,
+}
+
+impl Format {
+  // Picks the format for a file extension. Life 1.05 and 1.06 share `.lif` and `.life`, which are
+  // written as Life 1.05 since it keeps the rule; reading those files needs `detect` instead.
+  pub fn from_extension(path: &Path) -> Result<Format, String> {
+    match path.extension().and_then(|ext| ext.to_str()) {
+      Some("rle") => Ok(Format::Rle),
+      Some("cells") => Ok(Format::Plaintext),
+      Some("lif") | Some("life") => Ok(Format::Life105),
+      Some("txt") => Ok(Format::Grid),
+      _ => Err(format!("{}: unknown pattern file extension (expected .rle, .cells, .lif, .life or \
+                        .txt)", path.display())),
+    }
+  }
+
+  // Guesses the format from the contents of a file.
+  pub fn detect(input: &str) -> Option<Format> {
+    let lines: Vec<&str> = input.lines().map(|line| line.trim()).filter(|line| !line.is_empty()).collect();
+    let first = match lines.first() {
+      Some(line) => line,
+      None => return None,
+    };
+
+    if first.starts_with(life106::HEADER) {
+      Some(Format::Life106)
+    } else if first.starts_with(life105::HEADER) {
+      Some(Format::Life105)
+    } else if lines.iter().any(|line| line.starts_with('x') && line.contains('=')) {
+      Some(Format::Rle)
+    } else if lines.iter().all(|line| line.chars().all(|ch| ch == '#' || ch == '.')) {
+      Some(Format::Grid)
+    } else if lines.iter().all(|line| line.starts_with('!') || line.chars().all(|ch| ch == 'O' || ch == '.')) {
+      Some(Format::Plaintext)
+    } else {
+      None
+    }
+  }
+}
+
+pub fn read(input: &str, format: Format) -> Result<PatternFile, String> {
+  match format {
+    Format::Grid => {
+      // The grid format has no header, so it doesn't say which rule it was made for.
+      let mut pattern = PatternFile::from_board(&Board::parse(input.as_bytes()));
+      pattern.rule = None;
+      Ok(pattern)
+    }
+    Format::Rle => rle::read(input),
+    Format::Plaintext => plaintext::read(input),
+    Format::Life105 => life105::read(input),
+    Format::Life106 => life106::read(input),
+  }
+}
+
+// Fails if the format can't hold everything in the pattern. Only RLE takes any pattern.
+pub fn write(pattern: &PatternFile, format: Format) -> Result<String, String> {
+  match format {
+    Format::Grid => {
+      try!(require_conway(pattern, "grid"));
+      let mut grid = vec![vec!['.'; pattern.width]; pattern.height];
+      for &(r, c) in pattern.cells.iter() {
+        grid[r][c] = '#';
+      }
+      let mut out = String::new();
+      for row in grid {
+        out.extend(row);
+        out.push('\n');
+      }
+      Ok(out)
+    }
+    Format::Rle => Ok(rle::write(pattern)),
+    Format::Plaintext => plaintext::write(pattern),
+    Format::Life105 => life105::write(pattern),
+    Format::Life106 => life106::write(pattern),
+  }
+}
+
+// Reads a pattern in any supported format, going by the file extension when it is unambiguous
+// and by the contents otherwise.
+pub fn load(path: &Path) -> Result<PatternFile, String> {
+  let input = try!(read_to_string(path));
+  let format = match Format::from_extension(path) {
+    Ok(format @ Format::Rle) | Ok(format @ Format::Plaintext) => Some(format),
+    // `.lif` files may be either Life 1.05 or 1.06, and `.txt` files plaintext.
+    _ => Format::detect(&input),
+  };
+  let format = match format {
+    Some(format) => format,
+    None => return Err(format!("{}: unrecognized pattern format", path.display())),
+  };
+  read(&input, format).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+// Writes a pattern in the format matching the file extension.
+pub fn save(pattern: &PatternFile, path: &Path) -> Result<(), String> {
+  let format = try!(Format::from_extension(path));
+  let output = try!(write(pattern, format).map_err(|e| format!("{}: {}", path.display(), e)));
+  write_string(path, &output)
+}
+
+// Formats other than RLE only hold live cells, and at most a Life-like rule.
+fn require_life_like(pattern: &PatternFile, format: &str) -> Result<(), String> {
+  if !pattern.decaying.is_empty() {
+    return Err(format!("{} files can't hold decaying cells; use RLE instead", format));
+  }
+  match pattern.rule {
+    Some(rule) if !rule.is_life_like() => {
+      Err(format!("{} files can't hold rule {}; use RLE instead", format, rule))
+    }
+    _ => Ok(()),
+  }
+}
+
+// For formats with no rule at all, whose patterns are read as B3/S23 ones.
+fn require_conway(pattern: &PatternFile, format: &str) -> Result<(), String> {
+  try!(require_life_like(pattern, format));
+  match pattern.rule {
+    Some(rule) if rule != Rule::conway() => {
+      Err(format!("{} files can't hold rule {}; use RLE instead", format, rule))
+    }
+    _ => Ok(()),
+  }
+}
+
+// A pattern as stored in a file: its live cells within a `height` x `width` bounding box, plus
+// whatever metadata the format carries.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PatternFile {
+  pub name: Option<String>,
+  pub author: Option<String>,
+  pub comments: Vec<String>,
+  pub rule: Option<Rule>,
+  pub width: usize,
+  pub height: usize,
+  // Live cells as (row, col).
+  pub cells: Vec<(usize, usize)>,
+  // Cells in the decay states of a Generations rule, as (row, col, state). Only `ndgame` boards
+  // have decay states; see `ndgame::Board::from_pattern`.
+  pub decaying: Vec<(usize, usize, u8)>,
+}
+
+impl PatternFile {
+  pub fn from_board(board: &Board) -> PatternFile {
+    let (rows, cols) = board.size();
+    let mut cells = Vec::new();
+    for r in 0..rows {
+      for c in 0..cols {
+        if board[r][c] {
+          cells.push((r, c));
+        }
+      }
+    }
+
+    PatternFile {
+      rule: Some(board.rule()),
+      width: cols,
+      height: rows,
+      cells: cells,
+      ..Default::default()
+    }
+  }
+
+  // Builds a pattern from (row, col) coordinates that may be negative, as used by formats without
+  // a fixed origin. The pattern is shifted so its bounding box starts at (0, 0).
+  pub fn from_points(points: &[(isize, isize)]) -> PatternFile {
+    if points.is_empty() {
+      return PatternFile::default();
+    }
+
+    let r_min = points.iter().map(|&(r, _)| r).min().unwrap();
+    let r_max = points.iter().map(|&(r, _)| r).max().unwrap();
+    let c_min = points.iter().map(|&(_, c)| c).min().unwrap();
+    let c_max = points.iter().map(|&(_, c)| c).max().unwrap();
+    PatternFile {
+      width: (c_max - c_min + 1) as usize,
+      height: (r_max - r_min + 1) as usize,
+      cells: points.iter().map(|&(r, c)| ((r - r_min) as usize, (c - c_min) as usize)).collect(),
+      ..Default::default()
+    }
+  }
+
+  // Returns a board of at least `rows` x `cols` with the pattern in the middle. Like `to_board`,
+  // this panics if the pattern's rule has decay states.
+  pub fn to_board_centered(&self, rows: usize, cols: usize) -> Board {
+    let rows = cmp::max(rows, self.height);
+    let cols = cmp::max(cols, self.width);
+    let (r_start, c_start) = ((rows - self.height) / 2, (cols - self.width) / 2);
+    let mut board = Board::new(rows, cols).with_rule(self.rule.unwrap_or_default());
+    for &(r, c) in self.cells.iter() {
+      board[r_start + r][c_start + c] = true;
+    }
+    board
+  }
+
+  // Returns a board just large enough to hold the pattern, using the pattern's rule if it has one.
+  // game::Board can't run Generations rules, so this panics if the rule has decay states.
+  pub fn to_board(&self) -> Board {
+    let mut board = Board::new(self.height, self.width).with_rule(self.rule.unwrap_or_default());
+    for &(r, c) in self.cells.iter() {
+      board[r][c] = true;
+    }
+    board
+  }
+}
+
+fn read_to_string(path: &Path) -> Result<String, String> {
+  let mut s = String::new();
+  try!(File::open(path).and_then(|mut f| f.read_to_string(&mut s))
+    .map_err(|e| format!("{}: {}", path.display(), e)));
+  Ok(s)
+}
+
+fn write_string(path: &Path, s: &str) -> Result<(), String> {
+  File::create(path).and_then(|mut f| f.write_all(s.as_bytes()))
+    .map_err(|e| format!("{}: {}", path.display(), e))
+}
@@ -0,0 +1,78 @@
+// Plaintext (.cells) patterns, as described at http://www.conwaylife.com/wiki/Plaintext.
+//
+//   !Name: Glider
+//   !A comment
+//   .O.
+//   ..O
+//   OOO
+
+use std::cmp;
+use std::path::Path;
+
+use format::{self, PatternFile};
+
+pub fn read(input: &str) -> Result<PatternFile, String> {
+  let mut pattern = PatternFile::default();
+  let mut r = 0;
+
+  for line in input.lines() {
+    let line = line.trim_right();
+    if line.starts_with('!') {
+      let text = line[1..].trim();
+      if text.starts_with("Name:") {
+        pattern.name = Some(text["Name:".len()..].trim().to_string());
+      } else if text.starts_with("Author:") {
+        pattern.author = Some(text["Author:".len()..].trim().to_string());
+      } else {
+        pattern.comments.push(text.to_string());
+      }
+      continue;
+    }
+
+    for (c, ch) in line.chars().enumerate() {
+      match ch {
+        'O' | '*' => pattern.cells.push((r, c)),
+        '.' => (),
+        ch => return Err(format!("unexpected character '{}' in pattern", ch)),
+      }
+    }
+    pattern.width = cmp::max(pattern.width, line.len());
+    r += 1;
+  }
+
+  pattern.height = r;
+  Ok(pattern)
+}
+
+pub fn write(pattern: &PatternFile) -> Result<String, String> {
+  try!(format::require_conway(pattern, "Plaintext"));
+  let mut out = String::new();
+  if let Some(ref name) = pattern.name {
+    out.push_str(&format!("!Name: {}\n", name));
+  }
+  if let Some(ref author) = pattern.author {
+    out.push_str(&format!("!Author: {}\n", author));
+  }
+  for comment in pattern.comments.iter() {
+    out.push_str(&format!("!{}\n", comment));
+  }
+
+  let mut grid = vec![vec!['.'; pattern.width]; pattern.height];
+  for &(r, c) in pattern.cells.iter() {
+    grid[r][c] = 'O';
+  }
+  for row in grid {
+    out.extend(row);
+    out.push('\n');
+  }
+  Ok(out)
+}
+
+pub fn load(path: &Path) -> Result<PatternFile, String> {
+  read(&try!(format::read_to_string(path)))
+}
+
+pub fn save(pattern: &PatternFile, path: &Path) -> Result<(), String> {
+  let output = try!(write(pattern).map_err(|e| format!("{}: {}", path.display(), e)));
+  format::write_string(path, &output)
+}
@@ -0,0 +1,261 @@
+// Run Length Encoded (.rle) patterns, as described at http://www.conwaylife.com/wiki/RLE.
+//
+//   #N Glider
+//   #C A comment
+//   x = 3, y = 3, rule = B3/S23
+//   bob$2bo$3o!
+//
+// Patterns for rules with more than two states write `.` for dead cells and `A`, `B`, ... for
+// states 1, 2, ..., with states above 24 written as a lowercase prefix `p` to `y` and a letter.
+
+use std::path::Path;
+
+use format::{self, PatternFile};
+
+// Lines of the encoded body are wrapped to this length.
+const LINE_LENGTH: usize = 70;
+
+// Patterns reaching further than this from the origin are rejected, which also keeps run counts
+// from overflowing.
+const MAX_EXTENT: usize = 1 << 24;
+
+// States per multi-state prefix letter.
+const STATES_PER_PREFIX: u32 = 24;
+
+pub fn read(input: &str) -> Result<PatternFile, String> {
+  let mut pattern = PatternFile::default();
+  let mut header_seen = false;
+  let mut body = String::new();
+
+  for line in input.lines() {
+    let line = line.trim();
+    if line.is_empty() {
+      continue;
+    }
+    if !header_seen {
+      if line.starts_with('#') {
+        parse_comment(&mut pattern, line);
+      } else if line.starts_with('x') {
+        try!(parse_header(&mut pattern, line));
+        header_seen = true;
+      } else {
+        return Err(format!("expected 'x = ..., y = ...' header, found '{}'", line));
+      }
+      continue;
+    }
+
+    body.push_str(line);
+    if line.contains('!') {
+      break;
+    }
+  }
+
+  if !header_seen {
+    return Err("missing 'x = ..., y = ...' header".to_string());
+  }
+  try!(parse_body(&mut pattern, &body));
+  Ok(pattern)
+}
+
+fn parse_comment(pattern: &mut PatternFile, line: &str) {
+  let mut chars = line[1..].chars();
+  let kind = chars.next();
+  let text = chars.as_str().trim().to_string();
+  match kind {
+    Some('N') => pattern.name = Some(text),
+    Some('O') => pattern.author = Some(text),
+    Some('C') | Some('c') => pattern.comments.push(text),
+    // Golly writes the rule on a `#r` line in older files.
+    Some('r') => pattern.rule = text.parse().ok(),
+    _ => (),
+  }
+}
+
+fn parse_header(pattern: &mut PatternFile, line: &str) -> Result<(), String> {
+  for field in line.split(',') {
+    let mut kv = field.splitn(2, '=');
+    let key = kv.next().unwrap_or("").trim();
+    let value = match kv.next() {
+      Some(value) => value.trim(),
+      None => return Err(format!("invalid header field '{}'", field.trim())),
+    };
+    match key {
+      "x" => pattern.width = try!(value.parse().map_err(|_| format!("invalid width '{}'", value))),
+      "y" => pattern.height = try!(value.parse().map_err(|_| format!("invalid height '{}'", value))),
+      "rule" => pattern.rule = Some(try!(value.parse())),
+      _ => (),
+    }
+  }
+  Ok(())
+}
+
+fn parse_body(pattern: &mut PatternFile, body: &str) -> Result<(), String> {
+  let (mut r, mut c) = (0, 0);
+  let mut count: Option<usize> = None;
+  let mut prefix: Option<u32> = None;
+
+  for ch in body.chars() {
+    if let Some(digit) = ch.to_digit(10) {
+      let n = count.unwrap_or(0) * 10 + digit as usize;
+      if n > MAX_EXTENT {
+        return Err(format!("run count {}... is too large", n));
+      }
+      count = Some(n);
+      continue;
+    }
+    if prefix.is_none() && ch >= 'p' && ch <= 'y' {
+      prefix = Some(ch as u32 - 'p' as u32 + 1);
+      continue;
+    }
+
+    let n = count.take().unwrap_or(1);
+    match ch {
+      ch if prefix.is_some() && !(ch >= 'A' && ch <= 'X') => {
+        return Err(format!("unexpected character '{}' after a state prefix", ch));
+      }
+      'b' | '.' => c += n,
+      '$' => {
+        r += n;
+        c = 0;
+      }
+      '!' => break,
+      ch if ch >= 'A' && ch <= 'X' => {
+        let state = prefix.take().unwrap_or(0) * STATES_PER_PREFIX + (ch as u32 - 'A' as u32 + 1);
+        if state > 255 {
+          return Err(format!("state {} is out of range", state));
+        }
+        for i in 0..n {
+          if state == 1 {
+            pattern.cells.push((r, c + i));
+          } else {
+            pattern.decaying.push((r, c + i, state as u8));
+          }
+        }
+        c += n;
+      }
+      // Any other letter is a live cell.
+      ch if ch.is_alphabetic() => {
+        for i in 0..n {
+          pattern.cells.push((r, c + i));
+        }
+        c += n;
+      }
+      ch if ch.is_whitespace() => (),
+      ch => return Err(format!("unexpected character '{}' in pattern", ch)),
+    }
+    if r >= MAX_EXTENT || c > MAX_EXTENT {
+      return Err(format!("pattern is more than {} cells across", MAX_EXTENT));
+    }
+  }
+
+  // Prefixed states are also accepted in two-state files, so check them against the rule.
+  if let Some(rule) = pattern.rule {
+    if let Some(&(_, _, state)) = pattern.decaying.iter().find(|cell| cell.2 >= rule.states()) {
+      return Err(format!("state {} is out of range for rule {}", state, rule));
+    }
+  }
+
+  // Be lenient about headers that understate the pattern size.
+  let decaying: Vec<(usize, usize)> = pattern.decaying.iter().map(|&(r, c, _)| (r, c)).collect();
+  for &(r, c) in pattern.cells.iter().chain(decaying.iter()) {
+    if r >= pattern.height { pattern.height = r + 1; }
+    if c >= pattern.width { pattern.width = c + 1; }
+  }
+  Ok(())
+}
+
+pub fn write(pattern: &PatternFile) -> String {
+  let mut out = String::new();
+  if let Some(ref name) = pattern.name {
+    out.push_str(&format!("#N {}\n", name));
+  }
+  if let Some(ref author) = pattern.author {
+    out.push_str(&format!("#O {}\n", author));
+  }
+  for comment in pattern.comments.iter() {
+    out.push_str(&format!("#C {}\n", comment));
+  }
+
+  out.push_str(&format!("x = {}, y = {}", pattern.width, pattern.height));
+  if let Some(rule) = pattern.rule {
+    out.push_str(&format!(", rule = {}", rule));
+  }
+  out.push('\n');
+
+  let multi_state = !pattern.decaying.is_empty() ||
+    pattern.rule.map_or(false, |rule| rule.states() > 2);
+  let mut grid = vec![vec![0u8; pattern.width]; pattern.height];
+  for &(r, c) in pattern.cells.iter() {
+    grid[r][c] = 1;
+  }
+  for &(r, c, state) in pattern.decaying.iter() {
+    grid[r][c] = state;
+  }
+
+  let mut tokens = Vec::new();
+  let mut pending_rows = 0;
+  for row in grid.iter() {
+    // Trailing dead cells in a row are implied by the end-of-row marker.
+    let len = row.iter().rposition(|&state| state != 0).map(|c| c + 1).unwrap_or(0);
+    if len > 0 {
+      if pending_rows > 0 {
+        tokens.push(run(pending_rows, "$"));
+        pending_rows = 0;
+      }
+      let mut c = 0;
+      while c < len {
+        let state = row[c];
+        let start = c;
+        while c < len && row[c] == state {
+          c += 1;
+        }
+        tokens.push(run(c - start, &tag(state, multi_state)));
+      }
+    }
+    pending_rows += 1;
+  }
+  tokens.push("!".to_string());
+
+  let mut line = String::new();
+  for token in tokens {
+    if line.len() + token.len() > LINE_LENGTH {
+      out.push_str(&line);
+      out.push('\n');
+      line.clear();
+    }
+    line.push_str(&token);
+  }
+  out.push_str(&line);
+  out.push('\n');
+  out
+}
+
+fn run(n: usize, tag: &str) -> String {
+  if n == 1 { tag.to_string() } else { format!("{}{}", n, tag) }
+}
+
+// The letters for a cell in `state`.
+fn tag(state: u8, multi_state: bool) -> String {
+  if !multi_state {
+    return if state == 0 { "b" } else { "o" }.to_string();
+  }
+  if state == 0 {
+    return ".".to_string();
+  }
+  let index = state as u32 - 1;
+  let (prefix, letter) = (index / STATES_PER_PREFIX, index % STATES_PER_PREFIX);
+  let letter = (b'A' + letter as u8) as char;
+  if prefix == 0 {
+    letter.to_string()
+  } else {
+    format!("{}{}", (b'p' + prefix as u8 - 1) as char, letter)
+  }
+}
+
+pub fn load(path: &Path) -> Result<PatternFile, String> {
+  read(&try!(format::read_to_string(path)))
+}
+
+pub fn save(pattern: &PatternFile, path: &Path) -> Result<(), String> {
+  format::write_string(path, &write(pattern))
+}
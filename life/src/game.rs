@@ -1,78 +1,104 @@
-extern crate rand;
+use rand::Rng;
 
 use std::ops::{Index, IndexMut};
 
-const N: usize = 100;
+use automaton::{Automaton, Bounds};
+use rule::Rule;
+use topology::Topology;
 
 #[derive(Clone)]
 pub struct Board {
-  a: [[bool; N]; N],
+  a: Vec<bool>,
+  rows: usize,
+  cols: usize,
+  topology: Topology,
+  rule: Rule,
   pub generation: u64,
 }
 
 impl Board {
-  pub fn random() -> Board {
-    let mut a = [[false; N]; N];
-
-    for _ in 0..6400 {
-      let r = rand::random::<usize>() % a.len();
-      let c = rand::random::<usize>() % a[r].len();
-      a[r][c] = true;
-    }
-
+  pub fn new(rows: usize, cols: usize) -> Board {
     Board {
-      a: a,
+      a: vec![false; rows * cols],
+      rows: rows,
+      cols: cols,
+      topology: Topology::Bounded,
+      rule: Rule::conway(),
       generation: 0
     }
   }
 
-  pub fn empty() -> Board {
-    Board {
-      a: [[false; N]; N],
-      generation: 0
+  pub fn with_topology(mut self, topology: Topology) -> Board {
+    self.topology = topology;
+    self
+  }
+
+  pub fn with_rule(mut self, rule: Rule) -> Board {
+    assert!(rule.states() == 2, "{} has decay states, which game::Board can't store", rule);
+    self.rule = rule;
+    self
+  }
+
+  // Returns a board where each cell is alive with probability `density`.
+  pub fn random<R: Rng>(rows: usize, cols: usize, density: f32, rng: &mut R) -> Board {
+    let mut board = Board::new(rows, cols);
+    for cell in board.a.iter_mut() {
+      *cell = rng.gen::<f32>() < density;
     }
+    board
   }
 
+  // Parses a grid of `#` (alive) and `.` (dead) cells, one row per line. The board is as wide as
+  // the longest line; shorter lines are padded with dead cells.
   pub fn parse(x: &[u8]) -> Board {
-    let mut a = [[false; N]; N];
-
-    for r in 0..N {
-      for c in 0..N {
-        a[r][c] = match x[r*N + c] {
+    let lines: Vec<&[u8]> = x.split(|&b| b == b'\n')
+      .map(|line| if line.ends_with(b"\r") { &line[..line.len()-1] } else { line })
+      .filter(|line| !line.is_empty())
+      .collect();
+    let rows = lines.len();
+    let cols = lines.iter().map(|line| line.len()).max().unwrap_or(0);
+
+    let mut board = Board::new(rows, cols);
+    for (r, line) in lines.iter().enumerate() {
+      for (c, &b) in line.iter().enumerate() {
+        board[r][c] = match b {
           b'#' => true,
           b'.' => false,
           _    => false,
         }
       }
     }
-
-    Board {
-      a: a,
-      generation: 0
-    }
+    board
   }
 
   pub fn size(&self) -> (usize, usize) {
-    (N, N)
+    (self.rows, self.cols)
   }
 
   pub fn len(&self) -> usize {
-    N
+    self.rows
   }
 
-  pub fn neighbors(&self, r_start: usize, c_start: usize) -> u8 {
-    let mut count: u8 = 0;
-    for i in [-1, 0, 1].into_iter() {
-      for j in [-1, 0, 1].into_iter() {
-        if i == &0 && j == &0 {
-          continue;
-        }
+  pub fn topology(&self) -> Topology {
+    self.topology
+  }
+
+  pub fn rule(&self) -> Rule {
+    self.rule
+  }
+
+  // Counts the live cells in the rule's neighborhood of (r, c).
+  pub fn neighbors(&self, r: usize, c: usize) -> u8 {
+    self.count_neighbors(r, c, &self.rule.neighborhood().offsets())
+  }
 
-        let (r, c) = (r_start as i32 + i, c_start as i32 + j);
-        if r > 0 && (r as usize) < N && c > 0 && (c as usize) < N {
-          if self.a[r as usize][c as usize] {
-            count += 1;
-          }
+  fn count_neighbors(&self, r_start: usize, c_start: usize, offsets: &[(isize, isize)]) -> u8 {
+    let mut count: u8 = 0;
+    for &(i, j) in offsets.iter() {
+      let (r, c) = (r_start as isize + i, c_start as isize + j);
+      if let Some((r, c)) = self.topology.wrap(r, c, self.rows, self.cols) {
+        if self[r][c] {
+          count += 1;
         }
       }
     }
@@ -80,22 +106,23 @@ impl Board {
   }
 
   pub fn next(&self) -> Board {
-    let mut board = [[false; N]; N];
-    for r in 0..self.a.len() {
-      for c in 0..self.a[r].len() {
-        let n = self.neighbors(r, c);
-        if self.a[r][c] && (n == 2 || n == 3) {
-          board[r][c] = true;
-        } else if !self.a[r][c] && n == 3 {
-          board[r][c] = true;
-        }
+    let mut board = Board::new(self.rows, self.cols)
+      .with_topology(self.topology)
+      .with_rule(self.rule);
+    let offsets = self.rule.neighborhood().offsets();
+    for r in 0..self.rows {
+      for c in 0..self.cols {
+        let n = self.count_neighbors(r, c, &offsets);
+        board[r][c] = self.rule.next_state(self[r][c], n);
       }
     }
 
-    Board{ a: board, generation: self.generation + 1 }
+    board.generation = self.generation + 1;
+    board
   }
 
   pub fn difference(&self, other: &Board) -> usize {
+    assert_eq!(self.size(), other.size());
     let mut diff = 0;
     for r in 0..self.len() {
       for c in 0..self[r].len() {
@@ -109,13 +136,49 @@ impl Board {
 }
 
 impl Index<usize> for Board {
-  type Output = [bool; N];
-  fn index(&self, row_idx: usize) -> &[bool; N] {
-    &self.a[row_idx]
+  type Output = [bool];
+  fn index(&self, row_idx: usize) -> &[bool] {
+    &self.a[row_idx * self.cols..(row_idx + 1) * self.cols]
   }
 }
 impl IndexMut<usize> for Board {
-  fn index_mut(&mut self, row_idx: usize) -> &mut [bool; N] {
-    &mut self.a[row_idx]
+  fn index_mut(&mut self, row_idx: usize) -> &mut [bool] {
+    &mut self.a[row_idx * self.cols..(row_idx + 1) * self.cols]
+  }
+}
+
+impl Automaton for Board {
+  fn get(&self, r: i64, c: i64) -> bool {
+    r >= 0 && (r as usize) < self.rows && c >= 0 && (c as usize) < self.cols &&
+      self[r as usize][c as usize]
+  }
+
+  fn set(&mut self, r: i64, c: i64, alive: bool) {
+    assert!(r >= 0 && c >= 0, "cell ({}, {}) is outside the board", r, c);
+    self[r as usize][c as usize] = alive;
+  }
+
+  fn step(&mut self) {
+    *self = self.next();
+  }
+
+  fn population(&self) -> u64 {
+    self.a.iter().filter(|&&alive| alive).count() as u64
+  }
+
+  fn generation(&self) -> u64 {
+    self.generation
+  }
+
+  fn set_generation(&mut self, generation: u64) {
+    self.generation = generation;
+  }
+
+  fn bounds(&self) -> Bounds {
+    Bounds::new(0, 0, self.rows, self.cols)
+  }
+
+  fn rule(&self) -> Rule {
+    self.rule
   }
 }
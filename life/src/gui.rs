@@ -1,29 +1,63 @@
-use game::Board;
+use automaton::Automaton;
 
 use sdl2::pixels::Color;
 use sdl2::rect::Rect;
 use sdl2::render::Renderer;
 
+use std::cmp;
 use std::ops::Range;
 use std::option::Option;
 
+// Something `BoardRenderer` can draw on: an SDL window, or an in-memory `image::Framebuffer`.
+pub trait Canvas {
+  fn set_draw_color(&mut self, color: Color);
+  fn fill_rect(&mut self, rect: Rect) -> Result<(), String>;
+}
+
+impl<'a> Canvas for Renderer<'a> {
+  fn set_draw_color(&mut self, color: Color) {
+    Renderer::set_draw_color(self, color);
+  }
+
+  fn fill_rect(&mut self, rect: Rect) -> Result<(), String> {
+    Renderer::fill_rect(self, rect)
+  }
+}
+
+impl<'a, C: Canvas + ?Sized> Canvas for &'a mut C {
+  fn set_draw_color(&mut self, color: Color) {
+    (**self).set_draw_color(color);
+  }
+
+  fn fill_rect(&mut self, rect: Rect) -> Result<(), String> {
+    (**self).fill_rect(rect)
+  }
+}
+
+// Grid lines are only drawn when cells are at least this many pixels across.
+const MIN_GRID_CELL_SIZE: u32 = 4;
+
+// Limits on how far in and out the view can be zoomed, in cells across.
+const MIN_VIEW_CELLS: usize = 2;
+const MAX_VIEW_CELLS: usize = 1 << 16;
+
 #[derive(Clone)]
 #[derive(Debug)]
 pub struct BoardRect {
-  pub r: usize,
-  pub c: usize,
+  pub r: i64,
+  pub c: i64,
   pub rows: usize,
   pub cols: usize
 }
 impl BoardRect {
-  pub fn new(r: usize, c: usize, rows: usize, cols: usize) -> BoardRect {
+  pub fn new(r: i64, c: i64, rows: usize, cols: usize) -> BoardRect {
     BoardRect { r: r, c: c, rows: rows, cols: cols }
   }
-  fn row_range(&self) -> Range<usize> {
-    self.r..(self.r + self.rows)
+  fn row_range(&self) -> Range<i64> {
+    self.r..(self.r + self.rows as i64)
   }
-  fn col_range(&self) -> Range<usize> {
-    self.c..(self.c + self.cols)
+  fn col_range(&self) -> Range<i64> {
+    self.c..(self.c + self.cols as i64)
   }
 }
 
@@ -44,50 +78,165 @@ impl BoardRenderer {
     self
   }
 
-  pub fn draw(&self, board: &Board, renderer: &mut Renderer) -> Result<(), String> {
+  // The area of the canvas the board is drawn in.
+  pub fn draw_rect(&self) -> Rect {
+    self.draw_rect
+  }
+
+  // The part of `board` being shown: the board rect if one was set, or else the whole board.
+  pub fn board_rect<A: Automaton>(&self, board: &A) -> BoardRect {
+    self.board_rect.as_ref().cloned().unwrap_or_else(|| {
+      let bounds = board.bounds();
+      BoardRect::new(bounds.r, bounds.c, bounds.rows, bounds.cols)
+    })
+  }
+
+  // Changes the part of the board shown. None shows the whole board.
+  pub fn set_board_rect(&mut self, board_rect: Option<BoardRect>) {
+    self.board_rect = board_rect;
+  }
+
+  // Moves the view by (dr, dc) cells.
+  pub fn pan<A: Automaton>(&mut self, board: &A, dr: i64, dc: i64) {
+    let rect = self.board_rect(board);
+    self.board_rect = Some(BoardRect::new(rect.r + dr, rect.c + dc, rect.rows, rect.cols));
+  }
+
+  // How many cells (rows, cols) a movement of (dx, dy) pixels covers at the current zoom.
+  pub fn pixels_to_cells<A: Automaton>(&self, board: &A, dx: i32, dy: i32) -> (i64, i64) {
+    let rect = self.board_rect(board);
+    let (w, h) = self.draw_rect.size();
+    let dr = (dy as f64 * rect.rows as f64 / h as f64).round() as i64;
+    let dc = (dx as f64 * rect.cols as f64 / w as f64).round() as i64;
+    (dr, dc)
+  }
+
+  // Zooms in by `factor` (or out, for factors below 1), keeping the cell under pixel (x, y) in
+  // place.
+  pub fn zoom<A: Automaton>(&mut self, board: &A, factor: f64, x: i32, y: i32) {
+    let rect = self.board_rect(board);
+    let (w, h) = self.draw_rect.size();
+    let fy = (y - self.draw_rect.y()) as f64 / h as f64;
+    let fx = (x - self.draw_rect.x()) as f64 / w as f64;
+    let clamp = |n: usize| {
+      let zoomed = (n as f64 / factor).round() as usize;
+      cmp::min(MAX_VIEW_CELLS, cmp::max(MIN_VIEW_CELLS, zoomed))
+    };
+    let (rows, cols) = (clamp(rect.rows), clamp(rect.cols));
+    let r = rect.r + (fy * rect.rows as f64).round() as i64 - (fy * rows as f64).round() as i64;
+    let c = rect.c + (fx * rect.cols as f64).round() as i64 - (fx * cols as f64).round() as i64;
+    self.board_rect = Some(BoardRect::new(r, c, rows, cols));
+  }
+
+  // Works out where the cells and grid lines go when drawing `board`.
+  fn layout<A: Automaton>(&self, board: &A) -> Layout {
     let (w, h) = self.draw_rect.size();
-    let board_rect = self.board_rect.as_ref().cloned().unwrap_or(
-      BoardRect::new(0, 0, board.size().0, board.size().1)
-    );
+    let board_rect = self.board_rect(board);
+    if board_rect.rows == 0 || board_rect.cols == 0 {
+      return Layout { board_rect: board_rect, line_width: 0, cell_width: 1, cell_height: 1, cells_per_pixel: 1 };
+    }
+
+    // When there are more cells than pixels, each pixel stands for a square of cells.
+    let cells_per_pixel = cmp::max(
+      (board_rect.cols as u32 + w - 1) / w, (board_rect.rows as u32 + h - 1) / h);
+    if cells_per_pixel > 1 {
+      return Layout {
+        board_rect: board_rect,
+        line_width: 0,
+        cell_width: 1,
+        cell_height: 1,
+        cells_per_pixel: cells_per_pixel,
+      };
+    }
 
-    let line_width = 1;
+    // Drop the grid lines when zoomed out far enough that they would crowd out the cells.
+    let line_width = if w < MIN_GRID_CELL_SIZE * board_rect.cols as u32 ||
+      h < MIN_GRID_CELL_SIZE * board_rect.rows as u32 { 0 } else { 1 };
     let total_line_width_h = line_width * (board_rect.cols-1) as u32;
     let total_line_width_v = line_width * (board_rect.rows-1) as u32;
-    let cell_width = (w - total_line_width_h) / board_rect.cols as u32;
-    let cell_height = (h - total_line_width_v) / board_rect.rows as u32;
+    let cell_width = cmp::max(1, (w - total_line_width_h) / board_rect.cols as u32);
+    let cell_height = cmp::max(1, (h - total_line_width_v) / board_rect.rows as u32);
 
-    let total_cell_width = cell_width + line_width;
-    let total_cell_height = cell_height + line_width;
+    Layout {
+      board_rect: board_rect,
+      line_width: line_width,
+      cell_width: cell_width,
+      cell_height: cell_height,
+      cells_per_pixel: 1,
+    }
+  }
+
+  // Returns the cell of `board` drawn at pixel (x, y), if there is one.
+  pub fn cell_at<A: Automaton>(&self, board: &A, x: i32, y: i32) -> Option<(i64, i64)> {
+    let layout = self.layout(board);
+    let (x, y) = (x - self.draw_rect.x(), y - self.draw_rect.y());
+    if x < 0 || y < 0 {
+      return None;
+    }
+    let scale = layout.cells_per_pixel as i64;
+    let r = layout.board_rect.r + (y as u32 / layout.total_cell_height()) as i64 * scale;
+    let c = layout.board_rect.c + (x as u32 / layout.total_cell_width()) as i64 * scale;
+    let rect = &layout.board_rect;
+    if r < rect.r + rect.rows as i64 && c < rect.c + rect.cols as i64 && board.bounds().contains(r, c) {
+      Some((r, c))
+    } else {
+      None
+    }
+  }
+
+  pub fn draw<A: Automaton, C: Canvas>(&self, board: &A, renderer: &mut C) -> Result<(), String> {
+    let layout = self.layout(board);
+    let board_rect = &layout.board_rect;
+    if board_rect.rows == 0 || board_rect.cols == 0 {
+      return Ok(());
+    }
+    let line_width = layout.line_width;
+    let total_cell_width = layout.total_cell_width();
+    let total_cell_height = layout.total_cell_height();
 
     // Draw lines
 
     renderer.set_draw_color(Color::RGB(220,220,220));
 
-    for i in 1..board_rect.cols as u32 {
-      let offset = (i*total_cell_width - line_width) as i32;
-      try!(renderer.fill_rect(Rect::new(
-          self.draw_rect.x()+offset, self.draw_rect.y(), line_width, self.draw_rect.height()
-      )));
-    }
+    if line_width > 0 {
+      for i in 1..board_rect.cols as u32 {
+        let offset = (i*total_cell_width - line_width) as i32;
+        try!(renderer.fill_rect(Rect::new(
+            self.draw_rect.x()+offset, self.draw_rect.y(), line_width, self.draw_rect.height()
+        )));
+      }
 
-    for i in 1..board_rect.rows as u32 {
-      let offset = (i*total_cell_height - line_width) as i32;
-      try!(renderer.fill_rect(Rect::new(
-        self.draw_rect.x(), self.draw_rect.y()+offset, self.draw_rect.width(), line_width
-      )));
+      for i in 1..board_rect.rows as u32 {
+        let offset = (i*total_cell_height - line_width) as i32;
+        try!(renderer.fill_rect(Rect::new(
+          self.draw_rect.x(), self.draw_rect.y()+offset, self.draw_rect.width(), line_width
+        )));
+      }
     }
 
     // Draw blocks
 
-    renderer.set_draw_color(Color::RGB(50,50,220));
+    renderer.set_draw_color(state_color(1, 2));
+
+    if layout.cells_per_pixel > 1 {
+      return self.draw_aggregated(board, &layout, renderer);
+    }
 
+    // Cells decaying under a Generations rule get their own colours.
+    let states = board.rule().states();
+    let mut color = 1;
     for r in board_rect.row_range() {
       for c in board_rect.col_range() {
-        if board[r][c] {
+        let state = board.state(r, c);
+        if state > 0 {
+          if state != color {
+            renderer.set_draw_color(state_color(state, states));
+            color = state;
+          }
           let x = total_cell_width as i32 * (c - board_rect.c) as i32;
           let y = total_cell_height as i32 * (r - board_rect.r) as i32;
           try!(renderer.fill_rect(Rect::new(
-            self.draw_rect.x()+x, self.draw_rect.y()+y, cell_width, cell_height
+            self.draw_rect.x()+x, self.draw_rect.y()+y, layout.cell_width, layout.cell_height
           )));
         }
       }
@@ -95,4 +244,60 @@ impl BoardRenderer {
 
     Ok(())
   }
+
+  // Draws a pixel for every square of `layout.cells_per_pixel` cells that has any live cell in it.
+  fn draw_aggregated<A: Automaton, C: Canvas>(&self, board: &A, layout: &Layout, renderer: &mut C) -> Result<(), String> {
+    let rect = &layout.board_rect;
+    let scale = layout.cells_per_pixel as usize;
+    let (w, h) = ((rect.cols + scale - 1) / scale, (rect.rows + scale - 1) / scale);
+    let mut alive = vec![false; w * h];
+    for (r, c) in board.live_cells() {
+      if r >= rect.r && c >= rect.c && r < rect.r + rect.rows as i64 && c < rect.c + rect.cols as i64 {
+        let (y, x) = ((r - rect.r) as usize / scale, (c - rect.c) as usize / scale);
+        alive[y * w + x] = true;
+      }
+    }
+
+    for y in 0..h {
+      for x in 0..w {
+        if alive[y * w + x] {
+          try!(renderer.fill_rect(Rect::new(
+            self.draw_rect.x() + x as i32, self.draw_rect.y() + y as i32, 1, 1
+          )));
+        }
+      }
+    }
+    Ok(())
+  }
+}
+
+// Live cells are blue. Decaying cells fade from blue towards the background as they get closer to
+// dying.
+fn state_color(state: u8, states: u8) -> Color {
+  let (live, faded) = ((50, 50, 220), (235, 235, 250));
+  if state <= 1 || states <= 2 {
+    return Color::RGB(live.0, live.1, live.2);
+  }
+  let t = (state - 1) as f64 / (states - 1) as f64;
+  let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+  Color::RGB(mix(live.0, faded.0), mix(live.1, faded.1), mix(live.2, faded.2))
+}
+
+// Where `BoardRenderer` puts things: the part of the board shown and the size in pixels of each
+// cell and of the grid lines between them.
+struct Layout {
+  board_rect: BoardRect,
+  line_width: u32,
+  cell_width: u32,
+  cell_height: u32,
+  // Cells drawn per pixel across and down; more than 1 when zoomed out past one pixel per cell.
+  cells_per_pixel: u32,
+}
+impl Layout {
+  fn total_cell_width(&self) -> u32 {
+    self.cell_width + self.line_width
+  }
+  fn total_cell_height(&self) -> u32 {
+    self.cell_height + self.line_width
+  }
 }
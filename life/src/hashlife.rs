@@ -0,0 +1,464 @@
+// A Hashlife universe (Gosper's algorithm). The plane is a quadtree of macro-cells that are
+// canonicalized in a hash table, so identical regions are stored once, and the future of each
+// macro-cell is memoized. Regular patterns can then be advanced by 2^k generations in time that
+// depends on how much distinct structure they contain rather than on their area or age.
+//
+// The universe is unbounded: coordinates are signed, with (0, 0) at the center of the root node.
+//
+// Rules that give birth on zero neighbors would fill the plane and aren't supported.
+
+use std::collections::HashMap;
+use std::mem;
+
+use automaton::{Automaton, Bounds};
+use game::Board;
+use rule::Rule;
+
+type NodeId = usize;
+
+const DEAD: NodeId = 0;
+const ALIVE: NodeId = 1;
+
+// Once there are more nodes than this, the ones the current pattern no longer uses are thrown away
+// along with the memoized results, before the next step.
+const DEFAULT_NODE_LIMIT: usize = 1 << 21;
+
+// A square of 2^level x 2^level cells. Level 0 nodes are single cells and have no children.
+#[derive(Clone, Copy)]
+struct Node {
+  level: u8,
+  nw: NodeId,
+  ne: NodeId,
+  sw: NodeId,
+  se: NodeId,
+  population: u64,
+}
+
+fn leaf(population: u64) -> Node {
+  Node { level: 0, nw: DEAD, ne: DEAD, sw: DEAD, se: DEAD, population: population }
+}
+
+#[derive(Clone, Copy)]
+enum Side {
+  Top,
+  Bottom,
+  Left,
+  Right,
+}
+
+#[derive(Clone)]
+pub struct Universe {
+  nodes: Vec<Node>,
+  // Canonical node for each (nw, ne, sw, se) combination of children.
+  index: HashMap<(NodeId, NodeId, NodeId, NodeId), NodeId>,
+  // Memoized results: the center of a node advanced by 2^step generations.
+  results: HashMap<(NodeId, u8), NodeId>,
+  // The empty node of each level.
+  empty: Vec<NodeId>,
+  root: NodeId,
+  rule: Rule,
+  node_limit: usize,
+  pub generation: u64,
+}
+
+impl Universe {
+  pub fn new() -> Universe {
+    let mut universe = Universe {
+      nodes: vec![leaf(0), leaf(1)],
+      index: HashMap::new(),
+      results: HashMap::new(),
+      empty: vec![DEAD],
+      root: DEAD,
+      rule: Rule::conway(),
+      node_limit: DEFAULT_NODE_LIMIT,
+      generation: 0,
+    };
+    universe.root = universe.empty_node(3);
+    universe
+  }
+
+  pub fn with_rule(mut self, rule: Rule) -> Universe {
+    assert!(rule.is_life_like(), "hashlife::Universe only runs Life-like rules, not {}", rule);
+    assert!(!rule.born(0), "hashlife::Universe can't run {}, which gives birth on zero neighbors",
+            rule);
+    self.rule = rule;
+    self.results.clear();
+    self
+  }
+
+  // Collects garbage once there are more than `limit` nodes. Lower limits use less memory but
+  // throw away more of the memoized results.
+  pub fn with_node_limit(mut self, limit: usize) -> Universe {
+    self.node_limit = limit;
+    self
+  }
+
+  // Returns a universe holding `board`, with the board's top left cell at (0, 0). Cells outside
+  // the board start out dead regardless of its topology.
+  pub fn from_board(board: &Board) -> Universe {
+    let mut universe = Universe::new().with_rule(board.rule());
+    let (rows, cols) = board.size();
+    for r in 0..rows {
+      for c in 0..cols {
+        if board[r][c] {
+          universe.set_cell(r as i64, c as i64, true);
+        }
+      }
+    }
+    universe.generation = board.generation;
+    universe
+  }
+
+  // The smallest rectangle containing every live cell, or None if there aren't any.
+  pub fn bounding_box(&self) -> Option<Bounds> {
+    if self.nodes[self.root].population == 0 {
+      return None;
+    }
+
+    let half = 1i64 << (self.nodes[self.root].level - 1);
+    let inset = |side| self.inset(self.root, side, &mut HashMap::new()) as i64;
+    let (top, bottom, left, right) = (inset(Side::Top), inset(Side::Bottom), inset(Side::Left), inset(Side::Right));
+    Some(Bounds::new(-half + top, -half + left, (2 * half - top - bottom) as usize, (2 * half - left - right) as usize))
+  }
+
+  // Appends the live cells of `node`, whose top left corner is at (r, c).
+  fn collect_cells(&self, node: NodeId, r: i64, c: i64, cells: &mut Vec<(i64, i64)>) {
+    let n = self.nodes[node];
+    if n.population == 0 {
+      return;
+    }
+    if n.level == 0 {
+      cells.push((r, c));
+      return;
+    }
+    let half = 1i64 << (n.level - 1);
+    self.collect_cells(n.nw, r, c, cells);
+    self.collect_cells(n.ne, r, c + half, cells);
+    self.collect_cells(n.sw, r + half, c, cells);
+    self.collect_cells(n.se, r + half, c + half, cells);
+  }
+
+  // Distance from the given side of a non-empty node to its nearest live cell.
+  fn inset(&self, node: NodeId, side: Side, memo: &mut HashMap<NodeId, u64>) -> u64 {
+    let n = self.nodes[node];
+    if n.level == 0 {
+      return 0;
+    }
+    if let Some(&d) = memo.get(&node) {
+      return d;
+    }
+
+    let (near, far) = match side {
+      Side::Top => ([n.nw, n.ne], [n.sw, n.se]),
+      Side::Bottom => ([n.sw, n.se], [n.nw, n.ne]),
+      Side::Left => ([n.nw, n.sw], [n.ne, n.se]),
+      Side::Right => ([n.ne, n.se], [n.nw, n.sw]),
+    };
+    let (children, offset) = if near.iter().any(|&id| self.nodes[id].population > 0) {
+      (near, 0)
+    } else {
+      (far, 1 << (n.level - 1))
+    };
+    let d = offset + children.iter()
+      .filter(|&&id| self.nodes[id].population > 0)
+      .map(|&id| self.inset(id, side, memo))
+      .min().unwrap();
+    memo.insert(node, d);
+    d
+  }
+
+  fn get_cell(&self, r: i64, c: i64) -> bool {
+    let level = self.nodes[self.root].level;
+    let half = 1i64 << (level - 1);
+    if r < -half || r >= half || c < -half || c >= half {
+      return false;
+    }
+
+    let (mut r, mut c) = ((r + half) as u64, (c + half) as u64);
+    let mut node = self.root;
+    for level in (0..level).rev() {
+      let n = self.nodes[node];
+      let (south, east) = ((r >> level) & 1 == 1, (c >> level) & 1 == 1);
+      node = match (south, east) {
+        (false, false) => n.nw,
+        (false, true) => n.ne,
+        (true, false) => n.sw,
+        (true, true) => n.se,
+      };
+      r &= !(1 << level);
+      c &= !(1 << level);
+    }
+    node == ALIVE
+  }
+
+  fn set_cell(&mut self, r: i64, c: i64, alive: bool) {
+    loop {
+      let half = 1i64 << (self.nodes[self.root].level - 1);
+      if r >= -half && r < half && c >= -half && c < half {
+        let root = self.root;
+        self.root = self.set_in(root, (r + half) as u64, (c + half) as u64, alive);
+        return;
+      }
+      self.expand();
+    }
+  }
+
+  fn set_in(&mut self, node: NodeId, r: u64, c: u64, alive: bool) -> NodeId {
+    let n = self.nodes[node];
+    if n.level == 0 {
+      return if alive { ALIVE } else { DEAD };
+    }
+
+    let level = n.level - 1;
+    let (south, east) = ((r >> level) & 1 == 1, (c >> level) & 1 == 1);
+    let (r, c) = (r & !(1 << level), c & !(1 << level));
+    match (south, east) {
+      (false, false) => { let nw = self.set_in(n.nw, r, c, alive); self.join(nw, n.ne, n.sw, n.se) }
+      (false, true) => { let ne = self.set_in(n.ne, r, c, alive); self.join(n.nw, ne, n.sw, n.se) }
+      (true, false) => { let sw = self.set_in(n.sw, r, c, alive); self.join(n.nw, n.ne, sw, n.se) }
+      (true, true) => { let se = self.set_in(n.se, r, c, alive); self.join(n.nw, n.ne, n.sw, se) }
+    }
+  }
+
+  // Advances the universe by `generations`, one power of two at a time.
+  pub fn advance(&mut self, generations: u64) {
+    for k in 0..64 {
+      if (generations >> k) & 1 == 1 {
+        self.step_pow2(k as u8);
+      }
+    }
+  }
+
+  // Advances the universe by 2^k generations.
+  pub fn step_pow2(&mut self, k: u8) {
+    if self.nodes.len() > self.node_limit {
+      self.collect_garbage();
+    }
+    // The result of a node is its center half, which is only guaranteed to contain everything if
+    // the pattern sits in the center quarter and can't travel past the margin in 2^k generations.
+    while self.nodes[self.root].level < k + 2 || !self.is_padded() {
+      self.expand();
+    }
+    self.expand();
+
+    let root = self.root;
+    self.root = self.result(root, k);
+    self.generation += 1 << k;
+  }
+
+  fn is_padded(&mut self) -> bool {
+    let root = self.root;
+    if self.nodes[root].level < 3 {
+      return false;
+    }
+    let center = self.center(root);
+    let inner = self.center(center);
+    self.nodes[inner].population == self.nodes[root].population
+  }
+
+  // Doubles the size of the root node, keeping the existing root in the middle.
+  fn expand(&mut self) {
+    let n = self.nodes[self.root];
+    let e = self.empty_node(n.level - 1);
+    let nw = self.join(e, e, e, n.nw);
+    let ne = self.join(e, e, n.ne, e);
+    let sw = self.join(e, n.sw, e, e);
+    let se = self.join(n.se, e, e, e);
+    self.root = self.join(nw, ne, sw, se);
+  }
+
+  // Rebuilds the node table with only the nodes under the root, forgetting every memoized result.
+  fn collect_garbage(&mut self) {
+    let nodes = mem::replace(&mut self.nodes, vec![leaf(0), leaf(1)]);
+    self.index.clear();
+    self.results.clear();
+    self.empty = vec![DEAD];
+    let root = self.root;
+    self.root = self.copy_node(&nodes, root, &mut HashMap::new());
+  }
+
+  // Adds `node` from the old node table `nodes` to the current one.
+  fn copy_node(&mut self, nodes: &[Node], node: NodeId, copied: &mut HashMap<NodeId, NodeId>)
+               -> NodeId {
+    if node == DEAD || node == ALIVE {
+      return node;
+    }
+    if let Some(&id) = copied.get(&node) {
+      return id;
+    }
+    let n = nodes[node];
+    let nw = self.copy_node(nodes, n.nw, copied);
+    let ne = self.copy_node(nodes, n.ne, copied);
+    let sw = self.copy_node(nodes, n.sw, copied);
+    let se = self.copy_node(nodes, n.se, copied);
+    let id = self.join(nw, ne, sw, se);
+    copied.insert(node, id);
+    id
+  }
+
+  fn empty_node(&mut self, level: u8) -> NodeId {
+    while self.empty.len() <= level as usize {
+      let e = *self.empty.last().unwrap();
+      let next = self.join(e, e, e, e);
+      self.empty.push(next);
+    }
+    self.empty[level as usize]
+  }
+
+  fn join(&mut self, nw: NodeId, ne: NodeId, sw: NodeId, se: NodeId) -> NodeId {
+    let key = (nw, ne, sw, se);
+    if let Some(&id) = self.index.get(&key) {
+      return id;
+    }
+
+    let population = [nw, ne, sw, se].iter().map(|&id| self.nodes[id].population).sum();
+    let id = self.nodes.len();
+    self.nodes.push(Node {
+      level: self.nodes[nw].level + 1,
+      nw: nw,
+      ne: ne,
+      sw: sw,
+      se: se,
+      population: population,
+    });
+    self.index.insert(key, id);
+    id
+  }
+
+  // The node of half the size at the center of `node`.
+  fn center(&mut self, node: NodeId) -> NodeId {
+    let n = self.nodes[node];
+    let (nw, ne, sw, se) = (self.nodes[n.nw], self.nodes[n.ne], self.nodes[n.sw], self.nodes[n.se]);
+    self.join(nw.se, ne.sw, sw.ne, se.nw)
+  }
+
+  // Returns the center half of `node` advanced by 2^step generations, where step <= level - 2.
+  fn result(&mut self, node: NodeId, step: u8) -> NodeId {
+    let n = self.nodes[node];
+    if n.population == 0 {
+      return self.empty_node(n.level - 1);
+    }
+    if let Some(&id) = self.results.get(&(node, step)) {
+      return id;
+    }
+
+    let id = if n.level == 2 {
+      self.result_base(node)
+    } else {
+      let (nw, ne, sw, se) = (self.nodes[n.nw], self.nodes[n.ne], self.nodes[n.sw], self.nodes[n.se]);
+
+      // Nine overlapping subnodes of half the size, covering the node in a 3x3 grid.
+      let n00 = n.nw;
+      let n01 = self.join(nw.ne, ne.nw, nw.se, ne.sw);
+      let n02 = n.ne;
+      let n10 = self.join(nw.sw, nw.se, sw.nw, sw.ne);
+      let n11 = self.join(nw.se, ne.sw, sw.ne, se.nw);
+      let n12 = self.join(ne.sw, ne.se, se.nw, se.ne);
+      let n20 = n.sw;
+      let n21 = self.join(sw.ne, se.nw, sw.se, se.sw);
+      let n22 = n.se;
+
+      // At full speed both halves of the step advance the pattern; otherwise the first half just
+      // takes the center of each subnode and the second half does all of the stepping.
+      let full_speed = step == n.level - 2;
+      let first = |universe: &mut Universe, id| {
+        if full_speed { universe.result(id, step - 1) } else { universe.center(id) }
+      };
+      let r00 = first(self, n00);
+      let r01 = first(self, n01);
+      let r02 = first(self, n02);
+      let r10 = first(self, n10);
+      let r11 = first(self, n11);
+      let r12 = first(self, n12);
+      let r20 = first(self, n20);
+      let r21 = first(self, n21);
+      let r22 = first(self, n22);
+
+      let second_step = if full_speed { step - 1 } else { step };
+      let c_nw = self.join(r00, r01, r10, r11);
+      let c_ne = self.join(r01, r02, r11, r12);
+      let c_sw = self.join(r10, r11, r20, r21);
+      let c_se = self.join(r11, r12, r21, r22);
+      let nw = self.result(c_nw, second_step);
+      let ne = self.result(c_ne, second_step);
+      let sw = self.result(c_sw, second_step);
+      let se = self.result(c_se, second_step);
+      self.join(nw, ne, sw, se)
+    };
+
+    self.results.insert((node, step), id);
+    id
+  }
+
+  // Advances the center 2x2 cells of a 4x4 node by one generation.
+  fn result_base(&mut self, node: NodeId) -> NodeId {
+    let mut grid = [[false; 4]; 4];
+    let n = self.nodes[node];
+    for (i, &quadrant) in [n.nw, n.ne, n.sw, n.se].iter().enumerate() {
+      let q = self.nodes[quadrant];
+      for (j, &cell) in [q.nw, q.ne, q.sw, q.se].iter().enumerate() {
+        grid[(i / 2) * 2 + j / 2][(i % 2) * 2 + j % 2] = cell == ALIVE;
+      }
+    }
+
+    let mut next = [DEAD; 4];
+    for r in 1..3 {
+      for c in 1..3 {
+        let mut neighbors = 0;
+        for nr in r - 1..r + 2 {
+          for nc in c - 1..c + 2 {
+            if (nr, nc) != (r, c) && grid[nr][nc] {
+              neighbors += 1;
+            }
+          }
+        }
+        if self.rule.next_state(grid[r][c], neighbors) {
+          next[(r - 1) * 2 + (c - 1)] = ALIVE;
+        }
+      }
+    }
+    self.join(next[0], next[1], next[2], next[3])
+  }
+}
+
+impl Automaton for Universe {
+  fn get(&self, r: i64, c: i64) -> bool {
+    self.get_cell(r, c)
+  }
+
+  fn set(&mut self, r: i64, c: i64, alive: bool) {
+    self.set_cell(r, c, alive)
+  }
+
+  fn step(&mut self) {
+    self.step_pow2(0);
+  }
+
+  fn population(&self) -> u64 {
+    self.nodes[self.root].population
+  }
+
+  fn generation(&self) -> u64 {
+    self.generation
+  }
+
+  fn set_generation(&mut self, generation: u64) {
+    self.generation = generation;
+  }
+
+  fn bounds(&self) -> Bounds {
+    self.bounding_box().unwrap_or(Bounds::new(0, 0, 0, 0))
+  }
+
+  fn rule(&self) -> Rule {
+    self.rule
+  }
+
+  fn live_cells(&self) -> Vec<(i64, i64)> {
+    let mut cells = Vec::new();
+    let half = 1i64 << (self.nodes[self.root].level - 1);
+    self.collect_cells(self.root, -half, -half, &mut cells);
+    cells.sort();
+    cells
+  }
+}
@@ -0,0 +1,282 @@
+// A rewindable record of a board's past states. Each state is stored as the cells that changed
+// since the one before it, with a full copy of the board every so often so that going back a long
+// way doesn't mean undoing every generation in between. Old states are dropped once there are too
+// many, so memory stays bounded however long the board runs.
+
+use std::cmp;
+use std::collections::{BTreeSet, VecDeque};
+
+use automaton::Automaton;
+
+// How many states `History` keeps by default.
+const DEFAULT_CAPACITY: usize = 10000;
+
+// A full copy of the board is kept every this many states by default.
+const DEFAULT_KEYFRAME_INTERVAL: usize = 100;
+
+type Cell = (i64, i64);
+
+struct Entry<A> {
+  generation: u64,
+  // Whether the state came from editing the board rather than stepping it.
+  edit: bool,
+  // Cells that changed between the previous state and this one. Flipping them again goes back.
+  delta: Vec<Cell>,
+  keyframe: Option<A>,
+}
+
+pub struct History<A> {
+  board: A,
+  // The live cells of `board`, sorted, so each step only has to list the new ones.
+  live: Vec<Cell>,
+  entries: VecDeque<Entry<A>>,
+  // Index in `entries` of the state `board` is in.
+  position: usize,
+  capacity: usize,
+  keyframe_interval: usize,
+  bookmarks: BTreeSet<u64>,
+}
+
+impl<A: Automaton + Clone> History<A> {
+  pub fn new(board: A) -> History<A> {
+    let mut entries = VecDeque::new();
+    entries.push_back(Entry {
+      generation: board.generation(),
+      edit: false,
+      delta: Vec::new(),
+      keyframe: Some(board.clone()),
+    });
+    History {
+      live: sorted_cells(&board),
+      board: board,
+      entries: entries,
+      position: 0,
+      capacity: DEFAULT_CAPACITY,
+      keyframe_interval: DEFAULT_KEYFRAME_INTERVAL,
+      bookmarks: BTreeSet::new(),
+    }
+  }
+
+  // Keeps at most `capacity` states, dropping the oldest ones first.
+  pub fn with_capacity(mut self, capacity: usize) -> History<A> {
+    assert!(capacity > 0, "a history must keep at least one state");
+    self.capacity = capacity;
+    self
+  }
+
+  // Keeps a full copy of the board every `interval` states. Shorter intervals make jumping around
+  // faster and use more memory. Small capacities shorten the interval, so that there is always a
+  // keyframe to drop old states up to.
+  pub fn with_keyframe_interval(mut self, interval: usize) -> History<A> {
+    assert!(interval > 0, "the keyframe interval must be at least one state");
+    self.keyframe_interval = interval;
+    self
+  }
+
+  pub fn board(&self) -> &A {
+    &self.board
+  }
+
+  pub fn generation(&self) -> u64 {
+    self.board.generation()
+  }
+
+  // The oldest and newest generations that can be gone back to.
+  pub fn range(&self) -> (u64, u64) {
+    (self.entries.front().unwrap().generation, self.entries.back().unwrap().generation)
+  }
+
+  // Advances the board by a generation. After going back, this replays the recorded states
+  // (including edits) instead of stepping the board.
+  pub fn step(&mut self) {
+    if self.position + 1 < self.entries.len() {
+      let position = self.position + 1;
+      self.seek(position);
+      return;
+    }
+    self.board.step();
+    let live = sorted_cells(&self.board);
+    let delta = symmetric_difference(&self.live, &live);
+    self.live = live;
+    self.push(false, delta);
+  }
+
+  // Goes back a state, undoing the last step or edit. Returns false if there are no earlier
+  // states left.
+  pub fn back(&mut self) -> bool {
+    if self.position == 0 {
+      return false;
+    }
+    let position = self.position - 1;
+    self.seek(position);
+    true
+  }
+
+  // Goes to the last recorded state of `generation`, stepping the board forward if it hasn't got
+  // that far yet. It can step at most the history's capacity past the latest state, since going
+  // any further would take as long as it took and then keep none of the states in between.
+  pub fn goto(&mut self, generation: u64) -> Result<(), String> {
+    let (first, last) = self.range();
+    if generation < first {
+      return Err(format!("generation {} is no longer in the history, which starts at {}",
+                         generation, first));
+    }
+    if generation > last && generation - last > self.capacity as u64 {
+      return Err(format!("generation {} is too far ahead: the history only goes up to {} \
+                          generations past the latest, {}", generation, self.capacity, last));
+    }
+    if generation >= last {
+      let position = self.entries.len() - 1;
+      self.seek(position);
+      while self.generation() < generation {
+        self.step();
+      }
+      return Ok(());
+    }
+    let position = (0..self.entries.len()).rev()
+      .find(|&i| self.entries[i].generation <= generation)
+      .unwrap();
+    self.seek(position);
+    Ok(())
+  }
+
+  // Changes the board through `edit` and records the result as a new state, discarding any states
+  // that had been gone back from. Edits made one after another with nothing in between are undone
+  // together, so a stroke of the mouse is one state rather than one per cell.
+  pub fn edit<F: FnOnce(&mut A)>(&mut self, edit: F) {
+    edit(&mut self.board);
+    let live = sorted_cells(&self.board);
+    let delta = symmetric_difference(&self.live, &live);
+    self.live = live;
+    if delta.is_empty() {
+      return;
+    }
+
+    let latest = self.position + 1 == self.entries.len();
+    if latest && self.entries[self.position].edit {
+      let entry = &mut self.entries[self.position];
+      entry.delta = symmetric_difference(&entry.delta, &delta);
+      if entry.keyframe.is_some() {
+        entry.keyframe = Some(self.board.clone());
+      }
+    } else {
+      self.push(true, delta);
+    }
+  }
+
+  // Bookmarks the current generation, or removes the bookmark if it already has one.
+  pub fn toggle_bookmark(&mut self) {
+    let generation = self.generation();
+    if !self.bookmarks.remove(&generation) {
+      self.bookmarks.insert(generation);
+    }
+  }
+
+  pub fn bookmarks(&self) -> Vec<u64> {
+    self.bookmarks.iter().cloned().collect()
+  }
+
+  // The first bookmark after the current generation.
+  pub fn next_bookmark(&self) -> Option<u64> {
+    let generation = self.generation();
+    self.bookmarks.iter().cloned().find(|&g| g > generation)
+  }
+
+  // The last bookmark before the current generation.
+  pub fn previous_bookmark(&self) -> Option<u64> {
+    let generation = self.generation();
+    self.bookmarks.iter().cloned().rev().find(|&g| g < generation)
+  }
+
+  // Records the board as a new state following the current one.
+  fn push(&mut self, edit: bool, delta: Vec<Cell>) {
+    self.entries.truncate(self.position + 1);
+    let since_keyframe = self.entries.iter().rev().position(|e| e.keyframe.is_some()).unwrap();
+    // Keyframes at most half the capacity apart leave at least half of it after trimming.
+    let interval = cmp::min(self.keyframe_interval, cmp::max(1, self.capacity / 2));
+    let keyframe = if since_keyframe + 1 >= interval {
+      Some(self.board.clone())
+    } else {
+      None
+    };
+    self.entries.push_back(Entry {
+      generation: self.board.generation(),
+      edit: edit,
+      delta: delta,
+      keyframe: keyframe,
+    });
+    self.position = self.entries.len() - 1;
+
+    // Drop whole runs of states up to the next keyframe, so the oldest state always has one.
+    while self.entries.len() > self.capacity {
+      let next_keyframe = (1..self.entries.len()).find(|&i| self.entries[i].keyframe.is_some());
+      let next_keyframe = match next_keyframe {
+        Some(i) if i <= self.position => i,
+        _ => break,
+      };
+      self.entries.drain(..next_keyframe);
+      self.position -= next_keyframe;
+    }
+  }
+
+  // Puts the board in the state at `position`, starting from whichever of the current state and
+  // the nearest keyframes is closest.
+  fn seek(&mut self, position: usize) {
+    let mut from = self.position;
+    let distance = |a: usize, b: usize| if a > b { a - b } else { b - a };
+    let before = (0..position + 1).rev().find(|&i| self.entries[i].keyframe.is_some());
+    let after = (position..self.entries.len()).find(|&i| self.entries[i].keyframe.is_some());
+    for keyframe in before.into_iter().chain(after) {
+      if distance(keyframe, position) < distance(from, position) {
+        from = keyframe;
+      }
+    }
+    if from != self.position {
+      self.board = self.entries[from].keyframe.clone().unwrap();
+    }
+
+    // Flipping the cells in a state's delta moves between it and the state before it, in either
+    // direction.
+    let range: Vec<usize> = if from < position {
+      (from + 1..position + 1).collect()
+    } else {
+      (position + 1..from + 1).rev().collect()
+    };
+    for i in range {
+      for &(r, c) in self.entries[i].delta.iter() {
+        let alive = self.board.get(r, c);
+        self.board.set(r, c, !alive);
+      }
+    }
+    self.board.set_generation(self.entries[position].generation);
+    self.live = sorted_cells(&self.board);
+    self.position = position;
+  }
+}
+
+fn sorted_cells<A: Automaton>(board: &A) -> Vec<Cell> {
+  let mut cells = board.live_cells();
+  cells.sort();
+  cells
+}
+
+// The cells in exactly one of two sorted lists.
+fn symmetric_difference(a: &[Cell], b: &[Cell]) -> Vec<Cell> {
+  let mut diff = Vec::new();
+  let (mut i, mut j) = (0, 0);
+  while i < a.len() && j < b.len() {
+    if a[i] < b[j] {
+      diff.push(a[i]);
+      i += 1;
+    } else if b[j] < a[i] {
+      diff.push(b[j]);
+      j += 1;
+    } else {
+      i += 1;
+      j += 1;
+    }
+  }
+  diff.extend_from_slice(&a[i..]);
+  diff.extend_from_slice(&b[j..]);
+  diff
+}
@@ -0,0 +1,150 @@
+// Drawing boards without a display: `BoardRenderer` draws into an in-memory RGBA framebuffer,
+// which can be saved as a PNG, or strung together with later generations into an animated GIF.
+
+use gif;
+use png;
+
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+use automaton::Automaton;
+use gui::{BoardRenderer, Canvas};
+
+pub struct Framebuffer {
+  width: u32,
+  height: u32,
+  // `height` rows of `width` pixels, four bytes (red, green, blue, alpha) each.
+  pixels: Vec<u8>,
+  color: [u8; 4],
+}
+
+fn rgba(color: Color) -> [u8; 4] {
+  let (r, g, b, a) = color.rgba();
+  [r, g, b, a]
+}
+
+impl Framebuffer {
+  pub fn new(width: u32, height: u32, background: Color) -> Framebuffer {
+    let background = rgba(background);
+    let mut pixels = Vec::with_capacity((width * height * 4) as usize);
+    for _ in 0..width * height {
+      pixels.extend_from_slice(&background);
+    }
+    Framebuffer { width: width, height: height, pixels: pixels, color: [0, 0, 0, 255] }
+  }
+
+  pub fn width(&self) -> u32 {
+    self.width
+  }
+
+  pub fn height(&self) -> u32 {
+    self.height
+  }
+
+  pub fn pixels(&self) -> &[u8] {
+    &self.pixels
+  }
+
+  pub fn save_png(&self, path: &Path) -> Result<(), String> {
+    // Imported here because gif's `SetParameter` has a `set` method too.
+    use png::HasParameters;
+
+    let file = try!(File::create(path).map_err(|e| format!("{}: {}", path.display(), e)));
+    let mut encoder = png::Encoder::new(BufWriter::new(file), self.width, self.height);
+    encoder.set(png::ColorType::RGBA).set(png::BitDepth::Eight);
+    encoder.write_header()
+      .and_then(|mut writer| writer.write_image_data(&self.pixels))
+      .map_err(|e| format!("{}: {}", path.display(), e))
+  }
+}
+
+impl Canvas for Framebuffer {
+  fn set_draw_color(&mut self, color: Color) {
+    self.color = rgba(color);
+  }
+
+  fn fill_rect(&mut self, rect: Rect) -> Result<(), String> {
+    // Clip to the framebuffer, as SDL does.
+    let x0 = rect.x().max(0) as u32;
+    let y0 = rect.y().max(0) as u32;
+    let x1 = (rect.x() as i64 + rect.width() as i64).max(0).min(self.width as i64) as u32;
+    let y1 = (rect.y() as i64 + rect.height() as i64).max(0).min(self.height as i64) as u32;
+    for y in y0..y1 {
+      for x in x0..x1 {
+        let i = ((y * self.width + x) * 4) as usize;
+        self.pixels[i..i + 4].copy_from_slice(&self.color);
+      }
+    }
+    Ok(())
+  }
+}
+
+// Draws `board` on a white framebuffer big enough for `board_renderer`'s draw rect.
+pub fn render<A: Automaton>(board: &A, board_renderer: &BoardRenderer) -> Result<Framebuffer, String> {
+  let rect = board_renderer.draw_rect();
+  let (width, height) = ((rect.x() as u32 + rect.width()), (rect.y() as u32 + rect.height()));
+  let mut framebuffer = Framebuffer::new(width, height, Color::RGB(255, 255, 255));
+  try!(board_renderer.draw(board, &mut framebuffer));
+  Ok(framebuffer)
+}
+
+pub fn save_png<A: Automaton>(board: &A, board_renderer: &BoardRenderer, path: &Path) -> Result<(), String> {
+  try!(render(board, board_renderer)).save_png(path)
+}
+
+// Saves `generations` generations of `board`, starting with the current one, as a looping GIF
+// showing each generation for `delay` hundredths of a second.
+pub fn save_gif<A: Automaton + Clone>(board: &A, board_renderer: &BoardRenderer, generations: u32, delay: u16,
+                                      path: &Path) -> Result<(), String> {
+  use gif::SetParameter;
+
+  let mut board = board.clone();
+  let mut frames = Vec::new();
+  for _ in 0..generations {
+    frames.push(try!(render(&board, board_renderer)));
+    board.step();
+  }
+  if frames.is_empty() {
+    return Err("no generations to save".to_string());
+  }
+
+  // Boards only use a handful of colours, so they all fit in one global palette.
+  let mut palette = Vec::new();
+  let mut indices: HashMap<[u8; 3], u8> = HashMap::new();
+  let mut buffers = Vec::new();
+  for frame in frames.iter() {
+    let mut buffer = Vec::with_capacity((frame.width * frame.height) as usize);
+    for pixel in frame.pixels.chunks(4) {
+      let color = [pixel[0], pixel[1], pixel[2]];
+      if !indices.contains_key(&color) {
+        if indices.len() == 256 {
+          return Err("too many colours for a GIF".to_string());
+        }
+        indices.insert(color, indices.len() as u8);
+        palette.extend_from_slice(&color);
+      }
+      buffer.push(indices[&color]);
+    }
+    buffers.push(buffer);
+  }
+
+  let (width, height) = (frames[0].width as u16, frames[0].height as u16);
+  let file = try!(File::create(path).map_err(|e| format!("{}: {}", path.display(), e)));
+  let mut encoder = try!(gif::Encoder::new(BufWriter::new(file), width, height, &palette)
+    .map_err(|e| format!("{}: {}", path.display(), e)));
+  try!(encoder.set(gif::Repeat::Infinite).map_err(|e| format!("{}: {}", path.display(), e)));
+  for buffer in buffers {
+    let mut frame = gif::Frame::default();
+    frame.width = width;
+    frame.height = height;
+    frame.delay = delay;
+    frame.buffer = buffer.into();
+    try!(encoder.write_frame(&frame).map_err(|e| format!("{}: {}", path.display(), e)));
+  }
+  Ok(())
+}
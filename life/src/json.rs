@@ -0,0 +1,22 @@
+// Just enough JSON writing for the logs and sidecar files the search tools produce.
+
+// `s` as a quoted JSON string.
+pub fn string(s: &str) -> String {
+  let mut out = "\"".to_string();
+  for ch in s.chars() {
+    match ch {
+      '"' => out.push_str("\\\""),
+      '\\' => out.push_str("\\\\"),
+      '\n' => out.push_str("\\n"),
+      c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+      c => out.push(c),
+    }
+  }
+  out.push('"');
+  out
+}
+
+// `x` as a JSON number. JSON has no infinities or NaN, so those become null.
+pub fn number(x: f64) -> String {
+  if x.is_finite() { format!("{}", x) } else { "null".to_string() }
+}
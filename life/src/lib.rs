@@ -1,11 +1,33 @@
+extern crate crossbeam;
+extern crate getopts;
+extern crate gif;
 #[macro_use]
 extern crate ndarray;
+extern crate png;
 extern crate rand;
 extern crate sdl2;
 
 extern crate optimizer;
 
+pub mod automaton;
+pub mod bitgame;
+pub mod census;
+pub mod cycle;
+pub mod format;
 pub mod game;
 pub mod ndgame;
+pub mod neighborhood;
+pub mod options;
 pub mod gui;
+pub mod hashlife;
+pub mod history;
+pub mod image;
+pub mod json;
 pub mod pattern_finder;
+pub mod rule;
+pub mod seed;
+pub mod soup;
+pub mod spaceship;
+pub mod sparse;
+pub mod term;
+pub mod topology;
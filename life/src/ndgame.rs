@@ -1,20 +1,129 @@
 // Original example code taken from rust-ndarray project (MIT licensed).
 
+use crossbeam;
 use ndarray::prelude::*;
+use rand::Rng;
+
+use std::cmp;
+
+use automaton::{Automaton, Bounds};
+use format::PatternFile;
+use neighborhood::Neighborhood;
+use rule::Rule;
+use topology::Topology;
 
 pub const INPUT: &'static [u8] = include_bytes!("life.txt");
 //const INPUT: &'static [u8] = include_bytes!("lifelite.txt");
 
 pub type BoardArray = Array2<u8>;
 
+// Sets each cell of `neigh` to the number of live neighbors of the corresponding interior cell of
+// `z`, which has a one cell border on every side. Cells must be 0 or 1; see `alive_cells`. The
+// neighbors are given as `offsets` from the cell, none of them more than one cell away, and each
+// one adds a shifted view of `z`.
+fn count_neighbors(z: ArrayView2<u8>, neigh: &mut ArrayViewMut2<u8>, offsets: &[(isize, isize)]) {
+    let (n, m) = z.dim();
+    let (n, m) = (n as isize, m as isize);
+    neigh.fill(0);
+    for &(dr, dc) in offsets.iter() {
+        *neigh += &z.slice(s![1 + dr..n - 1 + dr, 1 + dc..m - 1 + dc]);
+    }
+}
+
+// Like `count_neighbors`, but for neighborhoods reaching further than the border. Builds a
+// summed-area table of the board padded by the neighborhood's range on every side (following the
+// topology), so each square is four lookups and each diamond is four lookups per row.
+fn count_larger(z: ArrayView2<u8>, neigh: &mut ArrayViewMut2<u8>, topology: Topology,
+                neighborhood: Neighborhood) {
+    let (rows, cols) = neigh.dim();
+    let range = neighborhood.range() as usize;
+    let (n, m) = (rows + 2 * range, cols + 2 * range);
+
+    // sat[[i, j]] is the sum of the padded cells above and to the left of (i, j).
+    let mut sat = Array2::<u32>::zeros((n + 1, m + 1));
+    for i in 0..n {
+        for j in 0..m {
+            let (r, c) = (i as isize - range as isize, j as isize - range as isize);
+            let cell = match topology.wrap(r, c, rows, cols) {
+                Some((r, c)) => z[[r + 1, c + 1]] as u32,
+                None => 0,
+            };
+            sat[[i + 1, j + 1]] = cell + sat[[i, j + 1]] + sat[[i + 1, j]] - sat[[i, j]];
+        }
+    }
+    // The sum of the padded cells in rows r0..r1 and columns c0..c1.
+    let sum = |r0: usize, c0: usize, r1: usize, c1: usize| {
+        sat[[r1, c1]] + sat[[r0, c0]] - sat[[r0, c1]] - sat[[r1, c0]]
+    };
+
+    for r in 0..rows {
+        for c in 0..cols {
+            // Cell (r, c) is at (r + range, c + range) in the padded board.
+            let count = match neighborhood {
+                Neighborhood::VonNeumann(_) => (0..2 * range + 1).map(|i| {
+                    let width = range - if i > range { i - range } else { range - i };
+                    sum(r + i, c + range - width, r + i + 1, c + range + width + 1)
+                }).sum(),
+                _ => sum(r, c, r + 2 * range + 1, c + 2 * range + 1),
+            };
+            neigh[[r, c]] = (count - z[[r + 1, c + 1]] as u32) as u8;
+        }
+    }
+}
+
+// Under a Generations rule, cells in a decay state (2 and up) don't count as neighbors, so they
+// are masked out into `alive` before counting. Returns false when the rule has no decay states and
+// `z` can be counted as it is.
+fn alive_cells(z: ArrayView2<u8>, rule: Rule, alive: &mut BoardArray) -> bool {
+    if rule.states() <= 2 {
+        return false;
+    }
+    if alive.dim() != z.dim() {
+        *alive = BoardArray::zeros(z.dim());
+    }
+    alive.zip_mut_with(&z, |a, &x| *a = (x == 1) as u8);
+    true
+}
+
+// Each cell holds its state: 0 for dead, 1 for alive, and 2 up to `rule.states() - 1` for the decay
+// states of Generations rules.
 #[derive(Clone, Debug)]
 pub struct Board {
+    // The cells surrounded by a one cell border on every side, so interior cell (r, c) is
+    // `arr[[r + 1, c + 1]]`. The border is refilled from the topology before every step.
     pub arr: BoardArray,
+    pub topology: Topology,
+    pub rule: Rule,
+    pub generation: u64,
+    // Reused by `alive_cells` so that Generations rules don't allocate every step.
+    alive: BoardArray,
 }
 
 impl Board {
+    // An empty `rows` x `cols` board, whose `arr` is (rows + 2, cols + 2) to make room for the
+    // border.
     pub fn blank(rows: usize, cols: usize) -> Board {
-        Board{arr: Array::zeros((rows, cols))}
+        Board{arr: Array::zeros((rows + 2, cols + 2)), topology: Topology::Bounded, rule: Rule::conway(), generation: 0,
+              alive: BoardArray::zeros((0, 0))}
+    }
+
+    pub fn with_topology(mut self, topology: Topology) -> Board {
+        self.topology = topology;
+        self
+    }
+
+    pub fn with_rule(mut self, rule: Rule) -> Board {
+        self.rule = rule;
+        self
+    }
+
+    // Returns a board where each cell is alive with probability `density`.
+    pub fn random<R: Rng>(rows: usize, cols: usize, density: f32, rng: &mut R) -> Board {
+        let mut board = Board::blank(rows, cols);
+        for cell in board.arr.slice_mut(s![1..-1, 1..-1]).iter_mut() {
+            *cell = if rng.gen::<f32>() < density { 1 } else { 0 };
+        }
+        board
     }
 
     pub fn parse(x: &[u8], rows: usize, cols: usize) -> Board {
@@ -28,43 +137,139 @@ impl Board {
 
         let a = a.into_shape((rows, cols)).unwrap();
         map.slice_mut(s![1..-1, 1..-1]).assign(&a);
-        Board{arr: map}
+        Board{arr: map, topology: Topology::Bounded, rule: Rule::conway(), generation: 0,
+              alive: BoardArray::zeros((0, 0))}
+    }
+
+    // Returns a board of at least `rows` x `cols` with the pattern, including any decaying cells,
+    // in the middle.
+    pub fn from_pattern(pattern: &PatternFile, rows: usize, cols: usize) -> Board {
+        let rows = cmp::max(rows, pattern.height);
+        let cols = cmp::max(cols, pattern.width);
+        let (r_start, c_start) = ((rows - pattern.height) / 2 + 1, (cols - pattern.width) / 2 + 1);
+        let mut board = Board::blank(rows, cols).with_rule(pattern.rule.unwrap_or_default());
+        for &(r, c) in pattern.cells.iter() {
+            board.arr[[r_start + r, c_start + c]] = 1;
+        }
+        for &(r, c, state) in pattern.decaying.iter() {
+            board.arr[[r_start + r, c_start + c]] = state;
+        }
+        board
+    }
+
+    pub fn to_pattern_file(&self) -> PatternFile {
+        let (rows, cols) = self.size();
+        let mut pattern = PatternFile {
+            rule: Some(self.rule),
+            width: cols,
+            height: rows,
+            ..Default::default()
+        };
+        for ((r, c), &state) in self.arr.slice(s![1..-1, 1..-1]).indexed_iter() {
+            match state {
+                0 => (),
+                1 => pattern.cells.push((r, c)),
+                _ => pattern.decaying.push((r, c, state)),
+            }
+        }
+        pattern
+    }
+
+    // Size of the board, not counting the padding border.
+    pub fn size(&self) -> (usize, usize) {
+        let (n, m) = self.arr.dim();
+        (n - 2, m - 2)
     }
 
     pub fn scratch(rows: usize, cols: usize) -> BoardArray {
         BoardArray::zeros((rows, cols))
     }
 
-    // Rules
-    //
-    // 2 or 3 neighbors: stay alive
-    // 3 neighbors: birth
-    // otherwise: death
-
     pub fn iterate(self: &mut Board, scratch: &mut BoardArray) {
+        self.fill_border();
+
         // compute number of neighbors
         let mut neigh = scratch.view_mut();
-        let z = &mut self.arr;
-        neigh.fill(0);
-        neigh += &z.slice(s![0..-2, 0..-2]);
-        neigh += &z.slice(s![0..-2, 1..-1]);
-        neigh += &z.slice(s![0..-2, 2..  ]);
+        let rule = self.rule;
+        let neighborhood = rule.neighborhood();
+        {
+            let masked = alive_cells(self.arr.view(), rule, &mut self.alive);
+            let z = if masked { self.alive.view() } else { self.arr.view() };
+            if neighborhood.range() > 1 {
+                count_larger(z, &mut neigh, self.topology, neighborhood);
+            } else {
+                count_neighbors(z, &mut neigh, &neighborhood.offsets());
+            }
+        }
 
-        neigh += &z.slice(s![1..-1, 0..-2]);
-        neigh += &z.slice(s![1..-1, 2..  ]);
+        // birth, survival or decay according to the rule
+        let mut zv = self.arr.slice_mut(s![1..-1, 1..-1]);
 
-        neigh += &z.slice(s![2..  , 0..-2]);
-        neigh += &z.slice(s![2..  , 1..-1]);
-        neigh += &z.slice(s![2..  , 2..  ]);
-
-        // birth where n = 3 and z[i] = 0,
-        // survive where n = 2 || n = 3 and z[i] = 1
-        let mut zv = z.slice_mut(s![1..-1, 1..-1]);
-
-        // this is autovectorized amazingly well!
         zv.zip_mut_with(&neigh, |y, &n| {
-            *y = ((n == 3) || (n == 2 && *y > 0)) as u8
+            *y = rule.next_cell(*y, n)
         });
+        self.generation += 1;
+    }
+
+    // Like `iterate`, but splits the board into bands of rows that are stepped on separate
+    // threads. Every band reads the row above and below it (its halo) from the current generation
+    // and writes its part of the next generation into `next`, which must be the size of the board,
+    // so the result is identical to `iterate`. Neighborhoods larger than the halo are stepped on
+    // one thread.
+    pub fn iterate_parallel(self: &mut Board, next: &mut BoardArray, threads: usize) {
+        if self.rule.neighborhood().range() > 1 {
+            return self.iterate(next);
+        }
+        self.fill_border();
+
+        let (rows, _) = self.size();
+        let band_rows = cmp::max(1, (rows + threads - 1) / cmp::max(1, threads));
+        let rule = self.rule;
+        {
+            let masked = alive_cells(self.arr.view(), rule, &mut self.alive);
+            let z = self.arr.view();
+            let counted = if masked { self.alive.view() } else { z };
+            let offsets = &rule.neighborhood().offsets();
+            crossbeam::scope(|scope| {
+                for (i, mut band) in next.axis_chunks_iter_mut(Axis(0), band_rows).enumerate() {
+                    let start = i * band_rows;
+                    let halo = z.slice(s![start..start + band.rows() + 2, ..]);
+                    let counted_halo = counted.slice(s![start..start + band.rows() + 2, ..]);
+                    scope.spawn(move || {
+                        count_neighbors(counted_halo, &mut band, offsets);
+                        band.zip_mut_with(&halo.slice(s![1..-1, 1..-1]), |n, &y| {
+                            *n = rule.next_cell(y, *n)
+                        });
+                    });
+                }
+            });
+        }
+
+        self.arr.slice_mut(s![1..-1, 1..-1]).assign(next);
+        self.generation += 1;
+    }
+
+    // Copies into the padding border the cells it stands for under this board's topology, so
+    // the neighbor sums in `iterate` see across glued edges.
+    fn fill_border(&mut self) {
+        let (n, m) = self.arr.dim();
+        for c in 0..m {
+            self.fill_border_cell(0, c);
+            self.fill_border_cell(n - 1, c);
+        }
+        for r in 1..n - 1 {
+            self.fill_border_cell(r, 0);
+            self.fill_border_cell(r, m - 1);
+        }
+    }
+
+    fn fill_border_cell(&mut self, r: usize, c: usize) {
+        let (n, m) = self.arr.dim();
+        let (r_in, c_in) = (r as isize - 1, c as isize - 1);
+        self.arr[[r, c]] = match self.topology.wrap(r_in, c_in, n - 2, m - 2) {
+            Some((r, c)) => self.arr[[r + 1, c + 1]],
+            None => 0,
+        };
     }
 
     pub fn turn_on_corners(self: &mut Board) {
@@ -77,3 +282,56 @@ impl Board {
         z[[n - 2, m - 2]] = 1;
     }
 }
+
+impl Automaton for Board {
+    fn get(&self, r: i64, c: i64) -> bool {
+        let (rows, cols) = self.size();
+        r >= 0 && (r as usize) < rows && c >= 0 && (c as usize) < cols &&
+            self.arr[[r as usize + 1, c as usize + 1]] == 1
+    }
+
+    fn state(&self, r: i64, c: i64) -> u8 {
+        let (rows, cols) = self.size();
+        if r >= 0 && (r as usize) < rows && c >= 0 && (c as usize) < cols {
+            self.arr[[r as usize + 1, c as usize + 1]]
+        } else {
+            0
+        }
+    }
+
+    fn set(&mut self, r: i64, c: i64, alive: bool) {
+        let (rows, cols) = self.size();
+        assert!(r >= 0 && (r as usize) < rows && c >= 0 && (c as usize) < cols,
+                "cell ({}, {}) is outside the board", r, c);
+        self.arr[[r as usize + 1, c as usize + 1]] = alive as u8;
+    }
+
+    // Allocates a scratch array on every call; use `iterate` directly to step repeatedly without
+    // that overhead.
+    fn step(&mut self) {
+        let (rows, cols) = self.size();
+        let mut scratch = Board::scratch(rows, cols);
+        self.iterate(&mut scratch);
+    }
+
+    fn population(&self) -> u64 {
+        self.arr.slice(s![1..-1, 1..-1]).iter().filter(|&&x| x == 1).count() as u64
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn set_generation(&mut self, generation: u64) {
+        self.generation = generation;
+    }
+
+    fn bounds(&self) -> Bounds {
+        let (rows, cols) = self.size();
+        Bounds::new(0, 0, rows, cols)
+    }
+
+    fn rule(&self) -> Rule {
+        self.rule
+    }
+}
@@ -0,0 +1,61 @@
+// Which cells count as a cell's neighbors. Rules normally use the eight cells around it (the
+// range 1 Moore neighborhood); Larger than Life rules reach further out.
+
+use std::cmp;
+
+// Largest range supported, so that neighbor counts fit in a u8.
+pub const MAX_RANGE: u8 = 7;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Neighborhood {
+  // The square of cells within `range` rows and columns.
+  Moore(u8),
+  // The diamond of cells within `range` steps up, down, left and right.
+  VonNeumann(u8),
+  // The six neighbors on a hexagonal grid, drawn as a square grid skewed so that each cell's
+  // neighbors are the eight Moore neighbors except the top right and bottom left ones (as in
+  // Golly).
+  Hexagonal,
+}
+
+impl Default for Neighborhood {
+  fn default() -> Neighborhood {
+    Neighborhood::Moore(1)
+  }
+}
+
+impl Neighborhood {
+  // How far from the cell its neighbors can be, in rows or columns.
+  pub fn range(&self) -> u8 {
+    match *self {
+      Neighborhood::Moore(range) | Neighborhood::VonNeumann(range) => range,
+      Neighborhood::Hexagonal => 1,
+    }
+  }
+
+  pub fn contains(&self, dr: isize, dc: isize) -> bool {
+    let range = self.range() as isize;
+    if (dr == 0 && dc == 0) || cmp::max(dr.abs(), dc.abs()) > range {
+      return false;
+    }
+    match *self {
+      Neighborhood::Moore(_) => true,
+      Neighborhood::VonNeumann(_) => dr.abs() + dc.abs() <= range,
+      Neighborhood::Hexagonal => (dr, dc) != (-1, 1) && (dr, dc) != (1, -1),
+    }
+  }
+
+  // The (row, col) offsets of a cell's neighbors, not including the cell itself.
+  pub fn offsets(&self) -> Vec<(isize, isize)> {
+    let range = self.range() as isize;
+    let mut offsets = Vec::new();
+    for dr in -range..range + 1 {
+      for dc in -range..range + 1 {
+        if self.contains(dr, dc) {
+          offsets.push((dr, dc));
+        }
+      }
+    }
+    offsets
+  }
+}
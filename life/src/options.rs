@@ -0,0 +1,57 @@
+// Command line options shared by the binaries, so that they are spelled, described and checked the
+// same way in each. Bad values are reported and end the program.
+
+use getopts::{Matches, Options};
+
+use std::process;
+use std::str::FromStr;
+
+use rule::Rule;
+
+pub fn add_rule(opts: &mut Options) {
+  opts.optopt("r", "rule", "rule in B/S, B/S/C or Larger than Life notation (default: the \
+                            pattern's rule, or B3/S23)", "RULE");
+}
+
+// The rule given with `--rule`, if there was one.
+pub fn rule(matches: &Matches) -> Option<Rule> {
+  match matches.opt_str("rule").map(|s| s.parse()) {
+    Some(Ok(rule)) => Some(rule),
+    Some(Err(e)) => { println!("{}", e); process::exit(1); }
+    None => None,
+  }
+}
+
+// The value of the option `name`, or `default` if it wasn't given.
+pub fn number<T: FromStr>(matches: &Matches, name: &str, default: T) -> T {
+  match matches.opt_str(name).map(|s| s.parse()) {
+    Some(Ok(n)) => n,
+    Some(Err(_)) => { println!("--{} must be a number", name); process::exit(1); }
+    None => default,
+  }
+}
+
+// Ends the program if `rule` has decay states, for binaries whose boards only have dead and alive
+// cells.
+pub fn require_two_states(rule: Rule) {
+  if rule.states() > 2 {
+    println!("{} is a Generations rule, which only ndlife, termlife and render can run", rule);
+    process::exit(1);
+  }
+}
+
+// Ends the program unless `rule` is Life-like, for binaries that run it on sparse boards. Those
+// can't run rules that give birth on zero neighbors either, which would fill the whole plane.
+pub fn require_life_like(rule: Rule) {
+  require_two_states(rule);
+  if !rule.is_life_like() {
+    println!("{} counts neighbors other than the eight surrounding cells, which only life, ndlife, \
+              termlife and render can run", rule);
+    process::exit(1);
+  }
+  if rule.born(0) {
+    println!("{} gives birth on zero neighbors, which only life, ndlife, termlife and render can \
+              run", rule);
+    process::exit(1);
+  }
+}
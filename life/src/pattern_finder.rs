@@ -1,75 +1,368 @@
 use optimizer::{Cost, Neighbor};
-use rand;
+use rand::Rng;
 
+use std::fmt;
+use std::str::FromStr;
+
+use automaton::{self, Automaton};
+use cycle::{self, Cycle, Kind};
+use format::PatternFile;
 use game::Board;
+use json;
+use spaceship::{self, Velocity};
+
+// Symmetries a candidate can be constrained to, named as in apgsearch.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Symmetry {
+  // No constraint.
+  C1,
+  // Unchanged by a half turn.
+  C2,
+  // Unchanged by a quarter turn.
+  C4,
+  // Mirrored left to right.
+  D2,
+  // Mirrored left to right and top to bottom.
+  D4,
+  // Unchanged by every rotation and reflection of the square.
+  D8,
+}
+
+impl Symmetry {
+  // The cells of a `size` x `size` grid that must have the same state as (r, c).
+  pub fn orbit(&self, r: usize, c: usize, size: usize) -> Vec<(usize, usize)> {
+    let (r2, c2) = (size - 1 - r, size - 1 - c);
+    let mut orbit = match *self {
+      Symmetry::C1 => vec![(r, c)],
+      Symmetry::C2 => vec![(r, c), (r2, c2)],
+      Symmetry::C4 => vec![(r, c), (c, r2), (r2, c2), (c2, r)],
+      Symmetry::D2 => vec![(r, c), (r, c2)],
+      Symmetry::D4 => vec![(r, c), (r, c2), (r2, c), (r2, c2)],
+      Symmetry::D8 => vec![(r, c), (r, c2), (r2, c), (r2, c2), (c, r), (c, r2), (c2, r), (c2, r2)],
+    };
+    orbit.sort();
+    orbit.dedup();
+    orbit
+  }
+}
+
+impl FromStr for Symmetry {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Symmetry, String> {
+    match &*s.to_uppercase() {
+      "C1" => Ok(Symmetry::C1),
+      "C2" => Ok(Symmetry::C2),
+      "C4" => Ok(Symmetry::C4),
+      "D2" => Ok(Symmetry::D2),
+      "D4" => Ok(Symmetry::D4),
+      "D8" => Ok(Symmetry::D8),
+      _ => Err(format!("unknown symmetry {:?}", s)),
+    }
+  }
+}
+
+impl fmt::Display for Symmetry {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    fmt::Debug::fmt(self, f)
+  }
+}
+
+// Things a search can look for. Each is scored between 0 (best) and 1 (worst).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Objective {
+  // Lots of cells changing between generations 16 and 20; the original cost.
+  Activity,
+  // Taking a long time to settle into a cycle, as methuselahs do.
+  Longevity,
+  // A large population once the pattern has settled.
+  FinalPopulation,
+  // Settling into an oscillator with a long period.
+  Period,
+  // Moving far each period, as spaceships do.
+  Displacement,
+  // The bounding box growing a lot over the run.
+  BoundingBoxGrowth,
+}
+
+impl FromStr for Objective {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Objective, String> {
+    match &*s.to_lowercase() {
+      "activity" => Ok(Objective::Activity),
+      "longevity" => Ok(Objective::Longevity),
+      "population" => Ok(Objective::FinalPopulation),
+      "period" => Ok(Objective::Period),
+      "displacement" => Ok(Objective::Displacement),
+      "growth" => Ok(Objective::BoundingBoxGrowth),
+      _ => Err(format!("unknown objective {:?}", s)),
+    }
+  }
+}
+
+// Writes the name `from_str` accepts.
+impl fmt::Display for Objective {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.write_str(match *self {
+      Objective::Activity => "activity",
+      Objective::Longevity => "longevity",
+      Objective::FinalPopulation => "population",
+      Objective::Period => "period",
+      Objective::Displacement => "displacement",
+      Objective::BoundingBoxGrowth => "growth",
+    })
+  }
+}
+
+// What a search looks for and how it explores candidates.
+#[derive(Clone, Debug)]
+pub struct PatternSearch {
+  pub candidate_size: usize,
+  pub symmetry: Symmetry,
+  // Cells (or orbits of cells, with a symmetry) flipped to get from a candidate to its neighbor.
+  pub flips: usize,
+  // Probability of each cell being alive in a random candidate.
+  pub density: f32,
+  // How long candidates are run for when looking for a cycle or measuring growth.
+  pub generations: u64,
+  // Objectives and their weights. With none, candidates are scored by `Objective::Activity`.
+  pub objectives: Vec<(Objective, f64)>,
+}
+
+impl Default for PatternSearch {
+  fn default() -> PatternSearch {
+    PatternSearch {
+      candidate_size: 10,
+      symmetry: Symmetry::C1,
+      flips: 1,
+      density: 0.3,
+      generations: 1000,
+      objectives: Vec::new(),
+    }
+  }
+}
+
+impl PatternSearch {
+  pub fn new() -> PatternSearch {
+    PatternSearch::default()
+  }
+
+  pub fn with_candidate_size(mut self, candidate_size: usize) -> PatternSearch {
+    self.candidate_size = candidate_size;
+    self
+  }
+
+  pub fn with_symmetry(mut self, symmetry: Symmetry) -> PatternSearch {
+    self.symmetry = symmetry;
+    self
+  }
+
+  pub fn with_flips(mut self, flips: usize) -> PatternSearch {
+    self.flips = flips;
+    self
+  }
+
+  pub fn with_density(mut self, density: f32) -> PatternSearch {
+    self.density = density;
+    self
+  }
+
+  pub fn with_generations(mut self, generations: u64) -> PatternSearch {
+    self.generations = generations;
+    self
+  }
 
-const CANDIDATE_SIZE: usize = 10;
-const NEIGHBOR_FLIPS: usize = 1;
-const INIT_CELL_PROB: f32   = 0.3;
+  pub fn with_objective(mut self, objective: Objective, weight: f64) -> PatternSearch {
+    self.objectives.push((objective, weight));
+    self
+  }
 
+  // The settings as a JSON object, for recording alongside search results.
+  pub fn to_json(&self) -> String {
+    let objectives: Vec<String> = self.objectives.iter()
+      .map(|&(objective, weight)| format!("{{\"objective\": {}, \"weight\": {}}}",
+                                          json::string(&objective.to_string()), json::number(weight)))
+      .collect();
+    format!("{{\"candidate_size\": {}, \"symmetry\": {}, \"flips\": {}, \"density\": {}, \
+             \"generations\": {}, \"objectives\": [{}]}}",
+            self.candidate_size, json::string(&self.symmetry.to_string()), self.flips,
+            self.density, self.generations, objectives.join(", "))
+  }
+}
+
+// A candidate pattern, evaluated by placing it in the middle of an otherwise empty board of type
+// `A` and running it.
 #[derive(Clone)]
-pub struct Pattern {
-  grid: [[bool; CANDIDATE_SIZE]; CANDIDATE_SIZE]
+pub struct Pattern<A: Automaton = Board> {
+  // `search.candidate_size` rows of `search.candidate_size` cells.
+  grid: Vec<bool>,
+  board: A,
+  search: PatternSearch,
 }
 
-impl Pattern {
-  pub fn random() -> Pattern {
-    let mut p = Pattern{grid: [[false; CANDIDATE_SIZE]; CANDIDATE_SIZE]};
-    for r in 0..CANDIDATE_SIZE {
-      for c in 0..CANDIDATE_SIZE {
-        if rand::random::<f32>() < INIT_CELL_PROB {
-          p.grid[r][c] = true;
+impl<A: Automaton + Clone> Pattern<A> {
+  // Returns a random candidate for `board`, which should be empty. Its rule is used to run the
+  // candidate.
+  pub fn random<R: Rng>(board: A, search: PatternSearch, rng: &mut R) -> Pattern<A> {
+    let size = search.candidate_size;
+    let mut p = Pattern{grid: vec![false; size * size], board: board, search: search};
+    for r in 0..size {
+      for c in 0..size {
+        // Each orbit is decided once, by its first cell.
+        let orbit = p.search.symmetry.orbit(r, c, size);
+        if orbit.iter().min() == Some(&(r, c)) && rng.gen::<f32>() < p.search.density {
+          for (r, c) in orbit {
+            p.grid[r * size + c] = true;
+          }
         }
       }
     }
     p
   }
 
-  // Returns a Board board that contains this candidate in the middle, but is otherwise empty.
-  pub fn starting_board(&self) -> Board {
-    let mut board = Board::empty();
-    let r_start = board.len() / 2 - CANDIDATE_SIZE / 2;
-    let c_start = board[0].len() / 2 - CANDIDATE_SIZE / 2;
-    for r in 0..CANDIDATE_SIZE {
-      for c in 0..CANDIDATE_SIZE {
-        board[r_start+r][c_start+c] = self.grid[r][c];
+  pub fn search(&self) -> &PatternSearch {
+    &self.search
+  }
+
+  // Returns a board that contains this candidate in the middle, but is otherwise empty.
+  pub fn starting_board(&self) -> A {
+    let size = self.search.candidate_size;
+    let mut board = self.board.clone();
+    let bounds = board.bounds();
+    let r_start = bounds.r + (bounds.rows / 2) as i64 - (size / 2) as i64;
+    let c_start = bounds.c + (bounds.cols / 2) as i64 - (size / 2) as i64;
+    for r in 0..size {
+      for c in 0..size {
+        board.set(r_start + r as i64, c_start + c as i64, self.grid[r * size + c]);
       }
     }
     board
   }
-}
 
-impl Cost for Pattern {
-  fn cost(&self) -> f64 {
+  // Runs the candidate until it repeats, for cost functions that care about how it ends up.
+  pub fn cycle(&self, max_generations: u64) -> Option<Cycle> {
+    cycle::find_cycle(&self.starting_board(), max_generations)
+  }
+
+  // The candidate's velocity if it turns into a spaceship, for searches targeting moving objects.
+  pub fn velocity(&self, max_generations: u64) -> Option<Velocity> {
+    spaceship::classify(&self.starting_board(), max_generations)
+  }
+
+  // Returns the candidate grid, e.g. for saving with `format::rle`.
+  pub fn to_pattern_file(&self) -> PatternFile {
+    let size = self.search.candidate_size;
+    let mut cells = Vec::new();
+    for r in 0..size {
+      for c in 0..size {
+        if self.grid[r * size + c] {
+          cells.push((r, c));
+        }
+      }
+    }
+    PatternFile {
+      rule: Some(self.board.rule()),
+      width: size,
+      height: size,
+      cells: cells,
+      ..Default::default()
+    }
+  }
+
+  fn activity_cost(&self) -> f64 {
     // Take the difference between the 16th and 20th iteration.
     // We want to maximize this, so count the proportion of cells that did not change as the cost.
     let mut board = self.starting_board();
     for _ in 0..16 {
-      board = board.next();
+      board.step();
     }
     let cost_start = board;
-    let mut board = cost_start.next();
-    for _ in 17..20 {
-        board = board.next();
+    let mut board = cost_start.clone();
+    for _ in 16..20 {
+      board.step();
     }
     let cost_end = board;
 
-    let total_cells = cost_start.len() * cost_start[0].len();
-    (total_cells - cost_end.difference(&cost_start)) as f64 / total_cells as f64
+    let bounds = automaton::union(cost_start.bounds(), cost_end.bounds());
+    let total_cells = bounds.rows * bounds.cols;
+    if total_cells == 0 {
+      return 1.;
+    }
+    (total_cells - automaton::difference(&cost_end, &cost_start)) as f64 / total_cells as f64
+  }
+
+  fn growth_cost(&self) -> f64 {
+    let area = |board: &A| automaton::live_bounds(board).map_or(0, |b| b.rows * b.cols);
+    let mut board = self.starting_board();
+    let start = area(&board);
+    for _ in 0..self.search.generations {
+      board.step();
+    }
+    let end = area(&board);
+    if end <= start { 1. } else { start as f64 / end as f64 }
+  }
+}
+
+impl<A: Automaton + Clone> Cost for Pattern<A> {
+  fn cost(&self) -> f64 {
+    if self.search.objectives.is_empty() {
+      return self.activity_cost();
+    }
+
+    // Only run the candidate to its cycle once, however many objectives need it.
+    let mut found = None;
+    let mut cycle = || *found.get_or_insert_with(|| self.cycle(self.search.generations));
+
+    let mut total = 0.;
+    let mut weights = 0.;
+    for &(objective, weight) in self.search.objectives.iter() {
+      let cost = match objective {
+        Objective::Activity => self.activity_cost(),
+        Objective::BoundingBoxGrowth => self.growth_cost(),
+        // Patterns that never settle within the limit are the longest lived of all.
+        Objective::Longevity => match cycle() {
+          Some(cycle) => 1. - cycle.transient as f64 / self.search.generations as f64,
+          None => 0.,
+        },
+        Objective::FinalPopulation => match cycle() {
+          Some(cycle) => 1. / (1. + cycle.population as f64),
+          None => 1.,
+        },
+        Objective::Period => match cycle() {
+          Some(ref cycle) if cycle.kind() != Kind::Died => 1. / cycle.period as f64,
+          _ => 1.,
+        },
+        Objective::Displacement => match cycle() {
+          Some(cycle) => {
+            let (dr, dc) = cycle.displacement;
+            1. / (1. + dr.abs().max(dc.abs()) as f64)
+          }
+          None => 1.,
+        },
+      };
+      total += weight * cost;
+      weights += weight;
+    }
+    if weights == 0. { 1. } else { total / weights }
   }
 }
 
-impl Neighbor for Pattern {
-  fn neighbor(&self) -> Pattern {
-    let mut new_grid = self.grid;
-    for _ in 0..NEIGHBOR_FLIPS {
-      let r = rand::random::<usize>() % new_grid.len();
-      let c = rand::random::<usize>() % new_grid[r].len();
+impl<A: Automaton + Clone> Neighbor for Pattern<A> {
+  fn neighbor<R: Rng>(&self, rng: &mut R) -> Pattern<A> {
+    let size = self.search.candidate_size;
+    let mut new_grid = self.grid.clone();
+    for _ in 0..self.search.flips {
+      let r = rng.gen_range(0, size);
+      let c = rng.gen_range(0, size);
 
-      // Take the inverse of self.grid's cell. In the case where the same cell gets chosen twice,
-      // this guarantees that there will still be a diference between self.grid and new_grid.
-      new_grid[r][c] = !self.grid[r][c];
+      // Take the inverse of self.grid's cells, flipping the whole orbit so the candidate keeps its
+      // symmetry. In the case where the same cell gets chosen twice, this guarantees that there
+      // will still be a diference between self.grid and new_grid.
+      for (r, c) in self.search.symmetry.orbit(r, c, size) {
+        new_grid[r * size + c] = !self.grid[r * size + c];
+      }
     }
-    Pattern{ grid: new_grid }
+    Pattern{ grid: new_grid, board: self.board.clone(), search: self.search.clone() }
   }
 }
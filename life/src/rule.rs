@@ -0,0 +1,335 @@
+use std::fmt;
+use std::str::FromStr;
+
+use neighborhood::{self, Neighborhood};
+
+// A set of neighbor counts, as a bitmask indexed by count.
+type Counts = [u64; 4];
+
+fn mask(counts: &[u8]) -> Counts {
+  let mut mask = [0; 4];
+  for &n in counts.iter() {
+    mask[n as usize / 64] |= 1 << (n % 64);
+  }
+  mask
+}
+
+fn contains(mask: &Counts, n: u8) -> bool {
+  (mask[n as usize / 64] >> (n % 64)) & 1 != 0
+}
+
+// An outer-totalistic rule: whether a cell is alive in the next generation depends only on
+// whether it is alive now and how many of its neighbors are. Each set is stored as a bitmask
+// indexed by neighbor count.
+//
+// Generations rules add decay states: a live cell that doesn't survive goes through states 2, 3,
+// ... up to `states - 1` before it is dead (state 0) again. Decaying cells aren't counted as
+// neighbors and can't be born into. Rules with two states are ordinary Life-like rules.
+//
+// The neighbors are normally the eight surrounding cells, but rules can use other neighborhoods,
+// including the larger ones of Larger than Life. Only `game` and `ndgame` boards support those;
+// the other boards only take Life-like rules (see `is_life_like`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rule {
+  birth: Counts,
+  survival: Counts,
+  states: u8,
+  neighborhood: Neighborhood,
+  // Whether a live cell counts itself as one of its neighbors, as in Larger than Life's `M1`.
+  middle: bool,
+}
+
+impl Rule {
+  pub fn new(birth: &[u8], survival: &[u8]) -> Rule {
+    Rule {
+      birth: mask(birth),
+      survival: mask(survival),
+      states: 2,
+      neighborhood: Neighborhood::default(),
+      middle: false,
+    }
+  }
+
+  // Makes this a Generations rule with `states` states in all, counting dead and alive.
+  pub fn with_states(mut self, states: u8) -> Rule {
+    assert!(states >= 2, "a rule needs at least two states");
+    self.states = states;
+    self
+  }
+
+  pub fn with_neighborhood(mut self, neighborhood: Neighborhood) -> Rule {
+    assert!(neighborhood.range() >= 1 && neighborhood.range() <= neighborhood::MAX_RANGE,
+            "neighborhood range must be 1 to {}", neighborhood::MAX_RANGE);
+    self.neighborhood = neighborhood;
+    self
+  }
+
+  pub fn with_middle(mut self, middle: bool) -> Rule {
+    self.middle = middle;
+    self
+  }
+
+  // B3/S23
+  pub fn conway() -> Rule {
+    Rule::new(&[3], &[2, 3])
+  }
+
+  pub fn born(&self, neighbors: u8) -> bool {
+    contains(&self.birth, neighbors)
+  }
+
+  // `neighbors` includes the cell itself if the rule counts the middle cell.
+  pub fn survives(&self, neighbors: u8) -> bool {
+    contains(&self.survival, neighbors)
+  }
+
+  pub fn states(&self) -> u8 {
+    self.states
+  }
+
+  pub fn neighborhood(&self) -> Neighborhood {
+    self.neighborhood
+  }
+
+  pub fn middle(&self) -> bool {
+    self.middle
+  }
+
+  // Whether this is a Life-like rule: two states, with neighbors counted among just the eight
+  // surrounding cells.
+  pub fn is_life_like(&self) -> bool {
+    self.states == 2 && self.neighborhood == Neighborhood::default() && !self.middle
+  }
+
+  // The next state for boards that store cells as bools, which only have the dead and alive
+  // states and so don't take Generations rules. `neighbors` never includes the cell itself.
+  pub fn next_state(&self, alive: bool, neighbors: u8) -> bool {
+    if alive { self.survives(neighbors + self.middle as u8) } else { self.born(neighbors) }
+  }
+
+  // The next state of a cell in state `state` (0 dead, 1 alive, 2 and up decaying).
+  pub fn next_cell(&self, state: u8, neighbors: u8) -> u8 {
+    match state {
+      0 => self.born(neighbors) as u8,
+      1 if self.survives(neighbors + self.middle as u8) => 1,
+      _ if state < self.states - 1 => state + 1,
+      _ => 0,
+    }
+  }
+}
+
+impl Default for Rule {
+  fn default() -> Rule {
+    Rule::conway()
+  }
+}
+
+fn parse_counts(s: &str) -> Result<Vec<u8>, String> {
+  s.chars().map(|ch| match ch.to_digit(10) {
+    Some(n) if n <= 8 => Ok(n as u8),
+    _ => Err(format!("invalid neighbor count '{}'", ch)),
+  }).collect()
+}
+
+fn parse_states(s: &str) -> Result<u8, String> {
+  match s.parse() {
+    Ok(n) if n >= 2 => Ok(n),
+    _ => Err(format!("invalid number of states '{}': expected 2 to 255", s)),
+  }
+}
+
+// Parses a Larger than Life count range: `34..58` (or `34-58`), a single count, or nothing.
+fn parse_range(s: &str) -> Result<Vec<u8>, String> {
+  if s.is_empty() {
+    return Ok(Vec::new());
+  }
+  let bounds: Vec<&str> = if s.contains("..") {
+    s.split("..").collect()
+  } else {
+    s.split('-').collect()
+  };
+  let parse = |n: &str| n.parse::<u8>().map_err(|_| format!("invalid neighbor count '{}'", n));
+  match bounds.len() {
+    1 => Ok(vec![try!(parse(bounds[0]))]),
+    2 => {
+      let (min, max) = (try!(parse(bounds[0])), try!(parse(bounds[1])));
+      Ok((min as u16..max as u16 + 1).map(|n| n as u8).collect())
+    }
+    _ => Err(format!("invalid range '{}'", s)),
+  }
+}
+
+// Parses Larger than Life rules in Golly's notation, such as Bosco's Rule
+// `R5,C0,M1,S34..58,B34..45,NM`: the range, the number of states (0 or 2 for two), whether the
+// middle cell counts, the survival and birth ranges, and the neighborhood (`NM` for a Moore square
+// or `NN` for a von Neumann diamond). Further ranges can follow the first, as in `S2..3,5`.
+fn parse_larger_than_life(s: &str) -> Result<Rule, String> {
+  let mut range = None;
+  let mut states = 2;
+  let mut middle = false;
+  let mut birth = Vec::new();
+  let mut survival = Vec::new();
+  let mut diamond = false;
+  let mut field = ' ';
+  for token in s.split(',').map(|token| token.trim()) {
+    let mut chars = token.chars();
+    let value = match chars.next() {
+      Some(ch) if ch.is_alphabetic() => {
+        field = ch.to_uppercase().next().unwrap();
+        chars.as_str()
+      }
+      _ => token,
+    };
+    match field {
+      'R' => {
+        range = Some(try!(value.parse::<u8>().map_err(|_| format!("invalid range '{}'", value))));
+      }
+      'C' if value == "0" => states = 2,
+      'C' => states = try!(parse_states(value)),
+      'M' => middle = match value {
+        "0" => false,
+        "1" => true,
+        _ => return Err(format!("invalid middle cell setting '{}': expected 0 or 1", value)),
+      },
+      'S' => survival.extend(try!(parse_range(value))),
+      'B' => birth.extend(try!(parse_range(value))),
+      'N' => diamond = match value {
+        "M" | "m" => false,
+        "N" | "n" => true,
+        _ => return Err(format!("unsupported neighborhood '{}': expected M or N", value)),
+      },
+      _ => return Err(format!("invalid rule '{}': unexpected '{}'", s, token)),
+    }
+  }
+
+  let range = match range {
+    Some(range) if range >= 1 && range <= neighborhood::MAX_RANGE => range,
+    _ => return Err(format!("invalid rule '{}': range must be 1 to {}", s,
+                            neighborhood::MAX_RANGE)),
+  };
+  let neighborhood = if diamond {
+    Neighborhood::VonNeumann(range)
+  } else {
+    Neighborhood::Moore(range)
+  };
+  Ok(Rule::new(&birth, &survival)
+     .with_states(states)
+     .with_neighborhood(neighborhood)
+     .with_middle(middle))
+}
+
+// Accepts `B36/S23` notation (in either order, case-insensitive) as well as the legacy `23/36`
+// form, which lists survival counts before birth counts. Generations rules have a third part
+// giving the number of states, as in `B2/S/C3` or the legacy `/2/3`. A trailing `H` or `V` picks
+// the hexagonal or von Neumann neighborhood, and rules starting with `R` are Larger than Life
+// rules (see `parse_larger_than_life`).
+impl FromStr for Rule {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Rule, String> {
+    let trimmed = s.trim();
+    if trimmed.starts_with('R') || trimmed.starts_with('r') {
+      return parse_larger_than_life(trimmed);
+    }
+    let (trimmed, neighborhood) = match trimmed.chars().last() {
+      Some('H') | Some('h') => (&trimmed[..trimmed.len() - 1], Neighborhood::Hexagonal),
+      Some('V') | Some('v') => (&trimmed[..trimmed.len() - 1], Neighborhood::VonNeumann(1)),
+      _ => (trimmed, Neighborhood::Moore(1)),
+    };
+
+    let parts: Vec<&str> = trimmed.split('/').collect();
+    if parts.len() != 2 && parts.len() != 3 {
+      return Err(format!("invalid rule '{}': expected two or three parts separated by '/'", s));
+    }
+
+    let mut birth = None;
+    let mut survival = None;
+    let mut states = None;
+    for part in parts.iter() {
+      let mut chars = part.chars();
+      match chars.next() {
+        Some('B') | Some('b') => birth = Some(try!(parse_counts(chars.as_str()))),
+        Some('S') | Some('s') => survival = Some(try!(parse_counts(chars.as_str()))),
+        Some('C') | Some('c') => states = Some(try!(parse_states(chars.as_str()))),
+        _ => (),
+      }
+    }
+
+    let rule = match (birth, survival, states) {
+      (Some(birth), Some(survival), _) => Rule::new(&birth, &survival),
+      (None, None, None) => {
+        let survival = try!(parse_counts(parts[0]));
+        let birth = try!(parse_counts(parts[1]));
+        if parts.len() == 3 {
+          states = Some(try!(parse_states(parts[2])));
+        }
+        Rule::new(&birth, &survival)
+      }
+      _ => return Err(format!("invalid rule '{}': expected both B and S parts", s)),
+    };
+    if parts.len() == 3 && states.is_none() {
+      return Err(format!("invalid rule '{}': expected a C part giving the number of states", s));
+    }
+    Ok(rule.with_states(states.unwrap_or(2)).with_neighborhood(neighborhood))
+  }
+}
+
+// Writes the counts in `mask` as Larger than Life ranges, such as `34..58`.
+fn write_ranges(f: &mut fmt::Formatter, mask: &Counts) -> fmt::Result {
+  let mut first = true;
+  let mut n = 0u16;
+  while n < 256 {
+    if !contains(mask, n as u8) {
+      n += 1;
+      continue;
+    }
+    let start = n;
+    while n < 256 && contains(mask, n as u8) {
+      n += 1;
+    }
+    if !first {
+      try!(write!(f, ","));
+    }
+    first = false;
+    if n - 1 == start {
+      try!(write!(f, "{}", start));
+    } else {
+      try!(write!(f, "{}..{}", start, n - 1));
+    }
+  }
+  Ok(())
+}
+
+impl fmt::Display for Rule {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let large_counts = (9..256).any(|n| self.born(n as u8) || self.survives(n as u8));
+    if self.neighborhood.range() > 1 || self.middle || large_counts {
+      let states = if self.states > 2 { self.states } else { 0 };
+      try!(write!(f, "R{},C{},M{},S", self.neighborhood.range(), states, self.middle as u8));
+      try!(write_ranges(f, &self.survival));
+      try!(write!(f, ",B"));
+      try!(write_ranges(f, &self.birth));
+      return match self.neighborhood {
+        Neighborhood::VonNeumann(_) => write!(f, ",NN"),
+        _ => write!(f, ",NM"),
+      };
+    }
+
+    try!(write!(f, "B"));
+    for n in 0..9 {
+      if self.born(n) { try!(write!(f, "{}", n)); }
+    }
+    try!(write!(f, "/S"));
+    for n in 0..9 {
+      if self.survives(n) { try!(write!(f, "{}", n)); }
+    }
+    if self.states > 2 {
+      try!(write!(f, "/C{}", self.states));
+    }
+    match self.neighborhood {
+      Neighborhood::Hexagonal => write!(f, "H"),
+      Neighborhood::VonNeumann(_) => write!(f, "V"),
+      Neighborhood::Moore(_) => Ok(()),
+    }
+  }
+}
@@ -0,0 +1,14 @@
+// Seeded random number generation. Everything random in the library takes its RNG as a
+// parameter, so a run started from `seeded_rng(seed)` can be replayed exactly from its seed.
+
+use rand::{self, IsaacRng, SeedableRng};
+
+// A random number generator whose output depends only on `seed`, on every platform.
+pub fn seeded_rng(seed: u64) -> IsaacRng {
+  IsaacRng::from_seed(&[seed as u32, (seed >> 32) as u32])
+}
+
+// A fresh seed, for runs that weren't given one. Printing it lets the run be replayed.
+pub fn random_seed() -> u64 {
+  rand::random::<u32>() as u64
+}
@@ -0,0 +1,111 @@
+// Soup searching: runs random starting patterns ("soups") until they settle down and takes a
+// census of what they leave behind. Every soup is generated from its own seed, so anything
+// interesting a search turns up can be reproduced from the seed alone.
+
+use automaton::Automaton;
+use census::{Catalogue, Census};
+use format::PatternFile;
+use game;
+use json;
+use seed;
+use sparse;
+
+// Longest period the population is checked for when deciding whether a soup has settled.
+const MAX_PERIOD: usize = 30;
+
+// A soup counts as settled once its population has repeated with some period for this many
+// generations in a row.
+const SETTLE_WINDOW: usize = 60;
+
+// Objects that turn up in almost every soup and aren't worth logging.
+const COMMON_OBJECTS: &'static [&'static str] = &[
+  "block", "beehive", "blinker", "loaf", "boat", "ship", "tub", "pond", "long boat", "barge",
+  "traffic light", "glider",
+];
+
+// A `size` x `size` soup where each cell is alive with probability `density`.
+pub fn random_soup(seed: u64, size: usize, density: f32) -> game::Board {
+  game::Board::random(size, size, density, &mut seed::seeded_rng(seed))
+}
+
+// Steps `board` until its population repeats with a period of at most `MAX_PERIOD` for
+// `SETTLE_WINDOW` generations, and returns the generation it settled at. Gives up and returns None
+// after `max_generations`.
+pub fn stabilize<A: Automaton>(board: &mut A, max_generations: u64) -> Option<u64> {
+  let mut populations = vec![board.population()];
+  let start = board.generation();
+
+  while board.generation() - start < max_generations {
+    board.step();
+    populations.push(board.population());
+
+    let n = populations.len();
+    let settled = (1..MAX_PERIOD + 1).any(|p| {
+      n >= SETTLE_WINDOW + p &&
+        (n - SETTLE_WINDOW..n).all(|i| populations[i] == populations[i - p])
+    });
+    if settled {
+      return Some(board.generation() - start - SETTLE_WINDOW as u64);
+    }
+  }
+  None
+}
+
+pub struct Soup {
+  pub seed: u64,
+  pub pattern: game::Board,
+  // Generations until the soup settled down, or None if it didn't within the limit.
+  pub lifespan: Option<u64>,
+  pub census: Census,
+}
+
+impl Soup {
+  // Runs the soup from `seed` under the catalogue's rule, taking its census from the catalogue.
+  pub fn run(seed: u64, size: usize, density: f32, catalogue: &Catalogue, max_generations: u64)
+             -> Soup {
+    let pattern = random_soup(seed, size, density).with_rule(catalogue.rule());
+    let mut board = sparse::Board::from_board(&pattern);
+    let lifespan = stabilize(&mut board, max_generations);
+    Soup {
+      seed: seed,
+      pattern: pattern,
+      lifespan: lifespan,
+      census: Census::take(&board, catalogue),
+    }
+  }
+
+  // Why this soup is worth keeping, if it is: it lived for at least `methuselah` generations,
+  // never settled, or left behind objects that aren't in `COMMON_OBJECTS`.
+  pub fn notable(&self, methuselah: u64) -> Vec<String> {
+    let mut reasons = Vec::new();
+    match self.lifespan {
+      Some(lifespan) if lifespan >= methuselah => reasons.push(format!("methuselah {}", lifespan)),
+      Some(_) => {}
+      None => reasons.push("unsettled".to_string()),
+    }
+    for name in self.census.counts.keys() {
+      if !COMMON_OBJECTS.contains(&name.as_str()) {
+        reasons.push(name.clone());
+      }
+    }
+    reasons
+  }
+
+  pub fn to_pattern_file(&self) -> PatternFile {
+    let mut pattern = PatternFile::from_board(&self.pattern);
+    pattern.name = Some(format!("soup {}", self.seed));
+    pattern
+  }
+
+  // One line of JSON describing the soup, for the search log.
+  pub fn to_json(&self, reasons: &[String]) -> String {
+    let lifespan = self.lifespan.map_or("null".to_string(), |n| n.to_string());
+    let reasons: Vec<String> = reasons.iter().map(|r| json::string(r)).collect();
+    let census: Vec<String> = self.census.counts.iter()
+      .map(|(name, count)| format!("{}: {}", json::string(name), count))
+      .collect();
+    format!("{{\"seed\": {}, \"rule\": {}, \"lifespan\": {}, \"reasons\": [{}], \"census\": {{{}}}}}",
+            self.seed, json::string(&self.pattern.rule().to_string()), lifespan,
+            reasons.join(", "), census.join(", "))
+  }
+}
@@ -0,0 +1,75 @@
+// Classifies patterns that travel across the board by their speed and direction, in the usual
+// notation: a glider is "c/4 diagonal", a lightweight spaceship "c/2 orthogonal".
+
+use std::fmt;
+
+use automaton::Automaton;
+use cycle::{self, Cycle, Kind};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+  Orthogonal,
+  Diagonal,
+  // Any other slope, e.g. knightwise ships that move (2, 1) per period.
+  Oblique,
+}
+
+// How far a spaceship moves, in (rows, cols), every `period` generations.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Velocity {
+  pub displacement: (i64, i64),
+  pub period: u64,
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+  if b == 0 { a } else { gcd(b, a % b) }
+}
+
+impl Velocity {
+  // The velocity of the pattern that `cycle` was found for, if it is a spaceship.
+  pub fn of_cycle(cycle: &Cycle) -> Option<Velocity> {
+    if cycle.kind() == Kind::Spaceship {
+      Some(Velocity { displacement: cycle.displacement, period: cycle.period })
+    } else {
+      None
+    }
+  }
+
+  pub fn direction(&self) -> Direction {
+    let (dr, dc) = (self.displacement.0.abs(), self.displacement.1.abs());
+    if dr == 0 || dc == 0 {
+      Direction::Orthogonal
+    } else if dr == dc {
+      Direction::Diagonal
+    } else {
+      Direction::Oblique
+    }
+  }
+
+  // Speed as a reduced fraction of c (one cell per generation), as (numerator, denominator).
+  pub fn speed(&self) -> (u64, u64) {
+    let distance = self.displacement.0.abs().max(self.displacement.1.abs()) as u64;
+    let d = gcd(distance, self.period);
+    if d == 0 { (0, 1) } else { (distance / d, self.period / d) }
+  }
+}
+
+impl fmt::Display for Velocity {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let (num, den) = self.speed();
+    let c = if num == 1 { "c".to_string() } else { format!("{}c", num) };
+    match self.direction() {
+      Direction::Orthogonal => write!(f, "{}/{} orthogonal", c, den),
+      Direction::Diagonal => write!(f, "{}/{} diagonal", c, den),
+      Direction::Oblique => {
+        let (dr, dc) = (self.displacement.0.abs(), self.displacement.1.abs());
+        write!(f, "({},{})c/{} oblique", dr.max(dc), dr.min(dc), self.period)
+      }
+    }
+  }
+}
+
+// Runs `board` until it repeats and returns its velocity if it turned out to be a spaceship.
+pub fn classify<A: Automaton + Clone>(board: &A, max_generations: u64) -> Option<Velocity> {
+  cycle::find_cycle(board, max_generations).and_then(|cycle| Velocity::of_cycle(&cycle))
+}
@@ -0,0 +1,209 @@
+// An unbounded board that only stores the parts of the plane that have live cells in them, as a
+// hash map of fixed-size tiles. Patterns can wander off in any direction, including into negative
+// coordinates, and the board grows tile by tile as they do, so spaceships and guns can run
+// indefinitely without ever hitting an edge.
+//
+// Rules that give birth on zero neighbors would fill the whole plane and aren't supported.
+
+use std::cmp;
+use std::collections::{HashMap, HashSet};
+
+use automaton::{Automaton, Bounds};
+use game;
+use rule::Rule;
+
+const TILE: usize = 16;
+
+// One row per u16, with bit `c` holding column `c`.
+type Tile = [u16; TILE];
+
+// Splits a coordinate into the index of the tile holding it and the offset within that tile.
+fn split(x: i64) -> (i64, usize) {
+  let t = TILE as i64;
+  let tile = if x >= 0 { x / t } else { (x + 1) / t - 1 };
+  (tile, (x - tile * t) as usize)
+}
+
+#[derive(Clone)]
+pub struct Board {
+  tiles: HashMap<(i64, i64), Tile>,
+  rule: Rule,
+  pub generation: u64,
+}
+
+impl Board {
+  pub fn new() -> Board {
+    Board {
+      tiles: HashMap::new(),
+      rule: Rule::conway(),
+      generation: 0,
+    }
+  }
+
+  pub fn with_rule(mut self, rule: Rule) -> Board {
+    assert!(rule.is_life_like(), "sparse::Board only runs Life-like rules, not {}", rule);
+    assert!(!rule.born(0), "sparse::Board can't run {}, which gives birth on zero neighbors", rule);
+    self.rule = rule;
+    self
+  }
+
+  // Returns a board holding the live cells of `board`, with its top left cell at (0, 0).
+  pub fn from_board(board: &game::Board) -> Board {
+    let mut sparse = Board::new().with_rule(board.rule());
+    let (rows, cols) = board.size();
+    for r in 0..rows {
+      for c in 0..cols {
+        if board[r][c] {
+          sparse.set(r as i64, c as i64, true);
+        }
+      }
+    }
+    sparse.generation = board.generation;
+    sparse
+  }
+
+  pub fn rule(&self) -> Rule {
+    self.rule
+  }
+
+  // The smallest rectangle containing every live cell, or None if there aren't any.
+  pub fn bounding_box(&self) -> Option<Bounds> {
+    let mut extent: Option<(i64, i64, i64, i64)> = None;
+    for (&(tr, tc), tile) in self.tiles.iter() {
+      for (r, &row) in tile.iter().enumerate() {
+        if row == 0 {
+          continue;
+        }
+        let r = tr * TILE as i64 + r as i64;
+        let c_min = tc * TILE as i64 + row.trailing_zeros() as i64;
+        let c_max = tc * TILE as i64 + (TILE as u32 - 1 - row.leading_zeros()) as i64;
+        extent = Some(match extent {
+          Some((r0, c0, r1, c1)) => (cmp::min(r0, r), cmp::min(c0, c_min), cmp::max(r1, r), cmp::max(c1, c_max)),
+          None => (r, c_min, r, c_max),
+        });
+      }
+    }
+    extent.map(|(r0, c0, r1, c1)| Bounds::new(r0, c0, (r1 - r0 + 1) as usize, (c1 - c0 + 1) as usize))
+  }
+
+  // Computes the next generation of the tile at (tr, tc) from it and its eight neighbors.
+  fn next_tile(&self, tr: i64, tc: i64) -> Tile {
+    let mut neighborhood = [[None; 3]; 3];
+    for dr in 0..3 {
+      for dc in 0..3 {
+        neighborhood[dr][dc] = self.tiles.get(&(tr + dr as i64 - 1, tc + dc as i64 - 1));
+      }
+    }
+
+    // The tile plus a one cell margin taken from its neighbors.
+    let mut window = [[false; TILE + 2]; TILE + 2];
+    for wr in 0..TILE + 2 {
+      for wc in 0..TILE + 2 {
+        let ((dr, r), (dc, c)) = (split(wr as i64 - 1), split(wc as i64 - 1));
+        if let Some(tile) = neighborhood[(dr + 1) as usize][(dc + 1) as usize] {
+          window[wr][wc] = (tile[r] >> c) & 1 == 1;
+        }
+      }
+    }
+
+    let mut next = [0; TILE];
+    for r in 0..TILE {
+      for c in 0..TILE {
+        let mut neighbors = 0;
+        for wr in r..r + 3 {
+          for wc in c..c + 3 {
+            if (wr, wc) != (r + 1, c + 1) && window[wr][wc] {
+              neighbors += 1;
+            }
+          }
+        }
+        if self.rule.next_state(window[r + 1][c + 1], neighbors) {
+          next[r] |= 1 << c;
+        }
+      }
+    }
+    next
+  }
+}
+
+impl Automaton for Board {
+  fn get(&self, r: i64, c: i64) -> bool {
+    let ((tr, r), (tc, c)) = (split(r), split(c));
+    self.tiles.get(&(tr, tc)).map_or(false, |tile| (tile[r] >> c) & 1 == 1)
+  }
+
+  fn set(&mut self, r: i64, c: i64, alive: bool) {
+    let ((tr, r), (tc, c)) = (split(r), split(c));
+    if alive {
+      self.tiles.entry((tr, tc)).or_insert([0; TILE])[r] |= 1 << c;
+    } else {
+      let empty = match self.tiles.get_mut(&(tr, tc)) {
+        Some(tile) => {
+          tile[r] &= !(1 << c);
+          tile.iter().all(|&row| row == 0)
+        }
+        None => false,
+      };
+      if empty {
+        self.tiles.remove(&(tr, tc));
+      }
+    }
+  }
+
+  fn step(&mut self) {
+    // Only tiles that have live cells or border one can have live cells next generation.
+    let mut candidates = HashSet::new();
+    for &(tr, tc) in self.tiles.keys() {
+      for dr in -1..2 {
+        for dc in -1..2 {
+          candidates.insert((tr + dr, tc + dc));
+        }
+      }
+    }
+
+    let mut tiles = HashMap::with_capacity(candidates.len());
+    for (tr, tc) in candidates {
+      let tile = self.next_tile(tr, tc);
+      if tile.iter().any(|&row| row != 0) {
+        tiles.insert((tr, tc), tile);
+      }
+    }
+    self.tiles = tiles;
+    self.generation += 1;
+  }
+
+  fn population(&self) -> u64 {
+    self.tiles.values().map(|tile| tile.iter().map(|row| row.count_ones() as u64).sum::<u64>()).sum()
+  }
+
+  fn generation(&self) -> u64 {
+    self.generation
+  }
+
+  fn set_generation(&mut self, generation: u64) {
+    self.generation = generation;
+  }
+
+  fn bounds(&self) -> Bounds {
+    self.bounding_box().unwrap_or(Bounds::new(0, 0, 0, 0))
+  }
+
+  fn rule(&self) -> Rule {
+    self.rule
+  }
+
+  fn live_cells(&self) -> Vec<(i64, i64)> {
+    let mut cells = Vec::new();
+    for (&(tr, tc), tile) in self.tiles.iter() {
+      for (r, &row) in tile.iter().enumerate() {
+        for c in 0..TILE {
+          if (row >> c) & 1 == 1 {
+            cells.push((tr * TILE as i64 + r as i64, tc * TILE as i64 + c as i64));
+          }
+        }
+      }
+    }
+    cells.sort();
+    cells
+  }
+}
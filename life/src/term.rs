@@ -0,0 +1,82 @@
+// Draws boards as text for terminals. Besides one character per cell, Unicode block and braille
+// characters pack two or eight cells into each character, so more of the board fits on screen.
+
+use std::str::FromStr;
+
+use automaton::{Automaton, Bounds};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Style {
+  // `#` and `.`, one cell per character.
+  Ascii,
+  // Half blocks, two cells (one above the other) per character.
+  HalfBlock,
+  // Braille dots, a two wide, four high square of cells per character.
+  Braille,
+}
+
+impl Style {
+  // How many cells (rows, cols) each character covers.
+  pub fn cells_per_char(&self) -> (usize, usize) {
+    match *self {
+      Style::Ascii => (1, 1),
+      Style::HalfBlock => (2, 1),
+      Style::Braille => (4, 2),
+    }
+  }
+}
+
+impl FromStr for Style {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Style, String> {
+    match &*s.to_lowercase() {
+      "ascii" => Ok(Style::Ascii),
+      "half" | "halfblock" => Ok(Style::HalfBlock),
+      "braille" => Ok(Style::Braille),
+      _ => Err(format!("unknown style {:?}; expected ascii, half or braille", s)),
+    }
+  }
+}
+
+// Braille dot for each cell of a character's square, indexed by [row][col].
+const BRAILLE_DOTS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+// The cells of `board` within `view` as lines of text, one per row of characters.
+pub fn render<A: Automaton>(board: &A, view: Bounds, style: Style) -> Vec<String> {
+  let (char_rows, char_cols) = style.cells_per_char();
+  let mut lines = Vec::new();
+  for y in 0..(view.rows + char_rows - 1) / char_rows {
+    let mut line = String::new();
+    for x in 0..(view.cols + char_cols - 1) / char_cols {
+      let r = view.r + (y * char_rows) as i64;
+      let c = view.c + (x * char_cols) as i64;
+      let alive = |dr: usize, dc: usize| {
+        let (r, c) = (r + dr as i64, c + dc as i64);
+        view.contains(r, c) && board.get(r, c)
+      };
+      line.push(match style {
+        Style::Ascii => if alive(0, 0) { '#' } else { '.' },
+        Style::HalfBlock => match (alive(0, 0), alive(1, 0)) {
+          (false, false) => ' ',
+          (true, false) => '\u{2580}',
+          (false, true) => '\u{2584}',
+          (true, true) => '\u{2588}',
+        },
+        Style::Braille => {
+          let mut dots = 0;
+          for (dr, row) in BRAILLE_DOTS.iter().enumerate() {
+            for (dc, &dot) in row.iter().enumerate() {
+              if alive(dr, dc) {
+                dots |= dot;
+              }
+            }
+          }
+          ::std::char::from_u32(0x2800 + dots).unwrap()
+        }
+      });
+    }
+    lines.push(line);
+  }
+  lines
+}
@@ -0,0 +1,64 @@
+// How the edges of a finite board are glued together. Both `game::Board` and `ndgame::Board` use
+// this to decide which cell a neighbor lookup off the edge of the board lands on.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Topology {
+  // Everything outside the board is dead.
+  Bounded,
+  // Opposite edges are joined, so patterns leaving one side come back on the other.
+  Torus,
+  // Left and right edges are joined; top and bottom are joined with a half twist, so crossing
+  // them mirrors the column.
+  KleinBottle,
+  // Both pairs of edges are joined with a half twist (the real projective plane).
+  CrossSurface,
+}
+
+impl Default for Topology {
+  fn default() -> Topology {
+    Topology::Bounded
+  }
+}
+
+// Reduces `x` into `0..n`, returning how many times it crossed an edge along the way.
+fn wrap_axis(x: isize, n: usize) -> (isize, usize) {
+  let n = n as isize;
+  let mut turns = x / n;
+  let mut x = x % n;
+  if x < 0 {
+    x += n;
+    turns -= 1;
+  }
+  (turns, x as usize)
+}
+
+impl Topology {
+  // Maps a coordinate that may lie outside a `rows` x `cols` board onto the cell it refers to, or
+  // returns None if it falls off a dead edge.
+  pub fn wrap(&self, r: isize, c: isize, rows: usize, cols: usize) -> Option<(usize, usize)> {
+    match *self {
+      Topology::Bounded => {
+        if r >= 0 && (r as usize) < rows && c >= 0 && (c as usize) < cols {
+          Some((r as usize, c as usize))
+        } else {
+          None
+        }
+      }
+      Topology::Torus => {
+        Some((wrap_axis(r, rows).1, wrap_axis(c, cols).1))
+      }
+      Topology::KleinBottle => {
+        let (r_turns, r) = wrap_axis(r, rows);
+        let c = if r_turns % 2 != 0 { cols as isize - 1 - c } else { c };
+        Some((r, wrap_axis(c, cols).1))
+      }
+      Topology::CrossSurface => {
+        let (r_turns, r) = wrap_axis(r, rows);
+        let c = if r_turns % 2 != 0 { cols as isize - 1 - c } else { c };
+        let (c_turns, c) = wrap_axis(c, cols);
+        let r = if c_turns % 2 != 0 { rows - 1 - r } else { r };
+        Some((r, c))
+      }
+    }
+  }
+}
@@ -0,0 +1,40 @@
+// bitgame::Board packs rows into 64-bit words, so it must agree with game::Board on every
+// topology, for boards narrower than a word, spanning several words and ending part way through
+// one.
+
+extern crate life;
+
+mod common;
+
+use life::automaton::Automaton;
+use life::bitgame;
+use life::game;
+use life::rule::Rule;
+use life::seed;
+
+use common::{cells, TOPOLOGIES};
+
+const SIZES: [(usize, usize); 3] = [(5, 130), (17, 70), (9, 9)];
+
+#[test]
+fn matches_game_board() {
+  let highlife: Rule = "B36/S23".parse().unwrap();
+  for &rule in &[Rule::conway(), highlife] {
+    for &topology in TOPOLOGIES.iter() {
+      for &(rows, cols) in SIZES.iter() {
+        let mut rng = seed::seeded_rng(rows as u64 * cols as u64);
+        let mut board = game::Board::random(rows, cols, 0.4, &mut rng)
+          .with_topology(topology)
+          .with_rule(rule);
+        let mut bits = bitgame::Board::from_board(&board);
+
+        for generation in 1..31 {
+          board.step();
+          bits.step();
+          assert_eq!(cells(&bits), cells(&board), "{}, {:?}, {}x{}, generation {}",
+                     rule, topology, rows, cols, generation);
+        }
+      }
+    }
+  }
+}
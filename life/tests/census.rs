@@ -0,0 +1,71 @@
+// Objects must be split apart and named the same way whatever phase they are in.
+
+extern crate life;
+
+mod common;
+
+use life::automaton::Automaton;
+use life::census::{self, Catalogue, Census};
+use life::rule::Rule;
+
+use common::rle_board;
+
+fn names(census: &Census) -> Vec<(String, usize)> {
+  census.counts.iter().map(|(name, &n)| (name.clone(), n)).collect()
+}
+
+// Two blinkers in opposite phases are never within two cells of each other in the same
+// generation, but each comes within two cells of where the other will be next generation, which
+// used to join them into a single xp2.
+#[test]
+fn blinkers_out_of_phase() {
+  let catalogue = Catalogue::new(Rule::conway());
+  let mut board = rle_board("5bo$3o2bo$5bo!");
+  for _ in 0..2 {
+    assert_eq!(census::objects(&board).len(), 2);
+    assert_eq!(names(&Census::take(&board, &catalogue)), vec![("blinker".to_string(), 2)]);
+    board.step();
+  }
+}
+
+// Pentadecathlons fall apart into pieces in some phases but are still one object.
+#[test]
+fn pentadecathlon_in_every_phase() {
+  let catalogue = Catalogue::new(Rule::conway());
+  let mut board = rle_board("2bo4bo$2ob4ob2o$2bo4bo!");
+  for _ in 0..15 {
+    assert_eq!(names(&Census::take(&board, &catalogue)), vec![("pentadecathlon".to_string(), 1)]);
+    board.step();
+  }
+}
+
+// The T tetromino settles into a traffic light: four blinkers close enough to count as one object.
+#[test]
+fn traffic_light() {
+  let catalogue = Catalogue::new(Rule::conway());
+  let mut board = rle_board("3o$bo!");
+  for _ in 0..10 {
+    board.step();
+  }
+  for _ in 0..2 {
+    assert_eq!(names(&Census::take(&board, &catalogue)), vec![("traffic light".to_string(), 1)]);
+    board.step();
+  }
+}
+
+// Other rules have no catalogue, so their objects are named by what they turn out to be.
+#[test]
+fn other_rules_describe_objects() {
+  let highlife: Rule = "B36/S23".parse().unwrap();
+  let catalogue = Catalogue::new(highlife);
+  let board = rle_board("2o$2o6b3o!").with_rule(highlife);
+  assert_eq!(names(&Census::take(&board, &catalogue)),
+             vec![("xp2".to_string(), 1), ("xs4".to_string(), 1)]);
+}
+
+#[test]
+#[should_panic]
+fn catalogue_must_match_the_rule() {
+  let catalogue = Catalogue::new(Rule::conway());
+  Census::take(&rle_board("2o$2o!").with_rule("B36/S23".parse().unwrap()), &catalogue);
+}
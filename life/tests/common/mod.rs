@@ -0,0 +1,23 @@
+// Helpers shared by the integration tests. Each test crate uses only some of them.
+#![allow(dead_code)]
+
+use life::automaton::Automaton;
+use life::format::rle;
+use life::sparse;
+use life::topology::Topology;
+
+pub const TOPOLOGIES: [Topology; 4] =
+  [Topology::Bounded, Topology::Torus, Topology::KleinBottle, Topology::CrossSurface];
+
+// The live cells of `board`, sorted so boards from different engines can be compared.
+pub fn cells<A: Automaton>(board: &A) -> Vec<(i64, i64)> {
+  let mut cells = board.live_cells();
+  cells.sort();
+  cells
+}
+
+// A sparse board holding the RLE pattern `body`, given without a header.
+pub fn rle_board(body: &str) -> sparse::Board {
+  let pattern = rle::read(&format!("x = 0, y = 0\n{}", body)).unwrap();
+  sparse::Board::from_board(&pattern.to_board())
+}
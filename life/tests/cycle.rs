@@ -0,0 +1,86 @@
+// Finding when patterns start repeating, and telling what they settle into from the cycle.
+
+extern crate life;
+
+mod common;
+
+use life::automaton::Automaton;
+use life::cycle::{self, Cycle, Kind};
+use life::sparse::Board;
+
+use common::rle_board;
+
+fn find_cycle(body: &str) -> Cycle {
+  cycle::find_cycle(&rle_board(body), 100).unwrap()
+}
+
+#[test]
+fn still_life() {
+  let block = find_cycle("2o$2o!");
+  assert_eq!(block, Cycle { transient: 0, period: 1, displacement: (0, 0), population: 4 });
+  assert_eq!(block.kind(), Kind::StillLife);
+
+  // An L tromino becomes a block after a generation.
+  let tromino = find_cycle("2o$o!");
+  assert_eq!(tromino, Cycle { transient: 1, period: 1, displacement: (0, 0), population: 4 });
+  assert_eq!(tromino.kind(), Kind::StillLife);
+}
+
+#[test]
+fn oscillator() {
+  let blinker = find_cycle("3o!");
+  assert_eq!(blinker, Cycle { transient: 0, period: 2, displacement: (0, 0), population: 3 });
+  assert_eq!(blinker.kind(), Kind::Oscillator);
+
+  let pulsar = find_cycle("2b3o3b3o2$o4bobo4bo$o4bobo4bo$o4bobo4bo$2b3o3b3o2$2b3o3b3o$o4bobo4bo$\
+                           o4bobo4bo$o4bobo4bo2$2b3o3b3o!");
+  assert_eq!((pulsar.period, pulsar.displacement), (3, (0, 0)));
+  assert_eq!(pulsar.kind(), Kind::Oscillator);
+}
+
+#[test]
+fn spaceships() {
+  let glider = find_cycle("bo$2bo$3o!");
+  assert_eq!(glider, Cycle { transient: 0, period: 4, displacement: (1, 1), population: 5 });
+  assert_eq!(glider.kind(), Kind::Spaceship);
+
+  let lwss = find_cycle("bo2bo$o$o3bo$4o!");
+  assert_eq!(lwss, Cycle { transient: 0, period: 4, displacement: (0, -2), population: 9 });
+  assert_eq!(lwss.kind(), Kind::Spaceship);
+}
+
+#[test]
+fn dies() {
+  // A domino dies straight away, and the empty board repeats from then on.
+  let domino = find_cycle("2o!");
+  assert_eq!(domino, Cycle { transient: 1, period: 1, displacement: (0, 0), population: 0 });
+  assert_eq!(domino.kind(), Kind::Died);
+}
+
+#[test]
+fn gives_up_after_max_generations() {
+  // The R-pentomino takes over a thousand generations to settle.
+  assert_eq!(cycle::find_cycle(&rle_board("b2o$2o$bo!"), 50), None);
+  // Whereas the glider only needs four.
+  assert!(cycle::find_cycle(&rle_board("bo$2bo$3o!"), 4).is_some());
+  assert_eq!(cycle::find_cycle(&rle_board("bo$2bo$3o!"), 3), None);
+}
+
+#[test]
+fn pattern_hash_ignores_position() {
+  let mut glider = rle_board("bo$2bo$3o!");
+  let (hash, bounds) = cycle::pattern_hash(&glider);
+  let mut moved = Board::new();
+  for (r, c) in glider.live_cells() {
+    moved.set(r - 40, c + 7, true);
+  }
+  let (moved_hash, moved_bounds) = cycle::pattern_hash(&moved);
+  assert_eq!(moved_hash, hash);
+  let (bounds, moved_bounds) = (bounds.unwrap(), moved_bounds.unwrap());
+  assert_eq!((moved_bounds.r, moved_bounds.c), (bounds.r - 40, bounds.c + 7));
+
+  // Other phases of the same glider are different patterns.
+  glider.step();
+  assert!(cycle::pattern_hash(&glider).0 != hash);
+  assert_eq!(cycle::pattern_hash(&Board::new()).1, None);
+}
@@ -0,0 +1,102 @@
+// Choosing a format from a file name, and refusing to write patterns a format can't hold rather
+// than silently dropping their rule or decay states.
+
+extern crate life;
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+
+use life::format::{self, Format, PatternFile};
+
+const FORMATS: [Format; 5] =
+  [Format::Grid, Format::Rle, Format::Plaintext, Format::Life105, Format::Life106];
+
+fn glider(rule: &str) -> PatternFile {
+  let mut pattern = PatternFile::from_points(&[(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]);
+  pattern.rule = Some(rule.parse().unwrap());
+  pattern
+}
+
+// Which formats can write `pattern`.
+fn writable(pattern: &PatternFile) -> Vec<Format> {
+  FORMATS.iter().cloned().filter(|&format| format::write(pattern, format).is_ok()).collect()
+}
+
+fn temp_path(name: &str) -> PathBuf {
+  env::temp_dir().join(format!("life-format-test-{}-{}", process::id(), name))
+}
+
+#[test]
+fn from_extension() {
+  let format = |name: &str| Format::from_extension(Path::new(name));
+  assert_eq!(format("glider.rle"), Ok(Format::Rle));
+  assert_eq!(format("glider.cells"), Ok(Format::Plaintext));
+  assert_eq!(format("glider.lif"), Ok(Format::Life105));
+  assert_eq!(format("dir/glider.life"), Ok(Format::Life105));
+  assert_eq!(format("glider.txt"), Ok(Format::Grid));
+  for name in &["glider.json", "glider.RLE", "glider"] {
+    assert!(format(name).is_err(), "{}", name);
+  }
+}
+
+#[test]
+fn formats_without_rules_only_take_conway() {
+  assert_eq!(writable(&glider("B3/S23")), FORMATS.to_vec());
+
+  let mut no_rule = glider("B3/S23");
+  no_rule.rule = None;
+  assert_eq!(writable(&no_rule), FORMATS.to_vec());
+
+  assert_eq!(writable(&glider("B36/S23")), vec![Format::Rle, Format::Life105]);
+  let written = format::write(&glider("B36/S23"), Format::Life105).unwrap();
+  let read = format::read(&written, Format::Life105).unwrap();
+  assert_eq!(read.rule, Some("B36/S23".parse().unwrap()));
+}
+
+#[test]
+fn only_rle_takes_other_rules_and_decay_states() {
+  for rule in &["B2/S/C3", "B2/S34H", "B1/S1V", "R2,C0,M1,S3..5,B4,NM"] {
+    assert_eq!(writable(&glider(rule)), vec![Format::Rle], "{}", rule);
+  }
+
+  // Decay states, even with no rule saying how many states there are.
+  let mut decaying = glider("B2/S/C3");
+  decaying.decaying.push((0, 0, 2));
+  assert_eq!(writable(&decaying), vec![Format::Rle]);
+  decaying.rule = None;
+  assert_eq!(writable(&decaying), vec![Format::Rle]);
+}
+
+#[test]
+fn save_and_load() {
+  // Plaintext and grid files have no rule, so they load without one.
+  let cases = [("rle", "B36/S23", Some("B36/S23")),
+               ("lif", "B36/S23", Some("B36/S23")),
+               ("life", "B3/S23", Some("B3/S23")),
+               ("cells", "B3/S23", None),
+               ("txt", "B3/S23", None)];
+  for &(extension, rule, loaded_rule) in cases.iter() {
+    let pattern = glider(rule);
+    let path = temp_path(&format!("save.{}", extension));
+    format::save(&pattern, &path).unwrap();
+    let loaded = format::load(&path);
+    fs::remove_file(&path).unwrap();
+
+    let loaded = loaded.unwrap();
+    assert_eq!(loaded.cells, pattern.cells, "{}", extension);
+    assert_eq!(loaded.rule, loaded_rule.map(|rule| rule.parse().unwrap()), "{}", extension);
+  }
+}
+
+#[test]
+fn save_rejects_unknown_extensions_and_unwritable_patterns() {
+  let path = temp_path("unknown.json");
+  assert!(format::save(&glider("B3/S23"), &path).is_err());
+  assert!(!path.exists());
+
+  let path = temp_path("highlife.cells");
+  assert!(format::save(&glider("B36/S23"), &path).is_err());
+  assert!(!path.exists());
+}
@@ -0,0 +1,104 @@
+// Generations rules: parsing, the decay of cells that don't survive, and writing decay states to
+// RLE files.
+
+extern crate life;
+extern crate rand;
+
+use life::automaton::Automaton;
+use life::format::rle;
+use life::game;
+use life::ndgame;
+use life::rule::Rule;
+use life::seed;
+
+use rand::Rng;
+
+#[test]
+fn parse_generations_rules() {
+  let brain: Rule = "B2/S/C3".parse().unwrap();
+  assert_eq!(brain.states(), 3);
+  assert!(brain.born(2) && !brain.born(3));
+  assert!((0..9).all(|n| !brain.survives(n)));
+  assert_eq!(brain.to_string(), "B2/S/C3");
+
+  // The legacy form lists survival, then birth, then the number of states.
+  assert_eq!("/2/3".parse::<Rule>().unwrap(), brain);
+  let star_wars: Rule = "345/2/4".parse().unwrap();
+  assert_eq!(star_wars, Rule::new(&[2], &[3, 4, 5]).with_states(4));
+  assert_eq!(star_wars.to_string(), "B2/S345/C4");
+
+  assert!("B2/S/C1".parse::<Rule>().is_err());
+  assert!("B2/S/3".parse::<Rule>().is_err());
+}
+
+#[test]
+fn next_cell() {
+  let rule = Rule::new(&[2], &[3, 4, 5]).with_states(4);
+  assert_eq!(rule.next_cell(0, 2), 1);
+  assert_eq!(rule.next_cell(0, 3), 0);
+  assert_eq!(rule.next_cell(1, 3), 1);
+  // Live cells that don't survive start to decay, and decaying cells carry on whatever their
+  // neighbors, until they are dead.
+  assert_eq!(rule.next_cell(1, 2), 2);
+  assert_eq!(rule.next_cell(2, 2), 3);
+  assert_eq!(rule.next_cell(2, 3), 3);
+  assert_eq!(rule.next_cell(3, 2), 0);
+
+  // With two states, cells that don't survive die straight away.
+  assert_eq!(Rule::conway().next_cell(1, 4), 0);
+}
+
+#[test]
+fn multi_state_rle_round_trip() {
+  let input = "x = 4, y = 1\n.ApAyO!\n";
+  let pattern = rle::read(input).unwrap();
+  assert_eq!(pattern.cells, vec![(0, 1)]);
+  assert_eq!(pattern.decaying, vec![(0, 2, 25), (0, 3, 255)]);
+  assert_eq!(rle::write(&pattern), input);
+
+  assert!(rle::read("x = 1, y = 1\nyP!").is_err());
+}
+
+#[test]
+#[should_panic]
+fn two_state_boards_reject_decay_states() {
+  game::Board::new(3, 3).with_rule("B2/S/C3".parse().unwrap());
+}
+
+// Decaying cells don't count as neighbors, so ndgame must agree with applying `next_cell` to
+// each cell's count of live neighbors.
+#[test]
+fn ndgame_matches_next_cell() {
+  let rule: Rule = "B2/S345/C6".parse().unwrap();
+  let (rows, cols) = (20, 20);
+  let mut rng = seed::seeded_rng(3);
+  let mut board = ndgame::Board::blank(rows, cols).with_rule(rule);
+  for r in 0..rows {
+    for c in 0..cols {
+      board.arr[[r + 1, c + 1]] = rng.gen_range(0, rule.states());
+    }
+  }
+
+  for _ in 0..10 {
+    let mut expected = vec![vec![0; cols]; rows];
+    for r in 0..rows as i64 {
+      for c in 0..cols as i64 {
+        let mut neighbors = 0;
+        for dr in -1..2 {
+          for dc in -1..2 {
+            if (dr, dc) != (0, 0) && board.get(r + dr, c + dc) {
+              neighbors += 1;
+            }
+          }
+        }
+        expected[r as usize][c as usize] = rule.next_cell(board.state(r, c), neighbors);
+      }
+    }
+    board.step();
+    for r in 0..rows {
+      for c in 0..cols {
+        assert_eq!(board.state(r as i64, c as i64), expected[r][c]);
+      }
+    }
+  }
+}
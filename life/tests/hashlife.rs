@@ -0,0 +1,80 @@
+// Hashlife must agree with stepping a game::Board one generation at a time, however it splits
+// the generations into powers of two and however often it collects garbage.
+
+extern crate life;
+extern crate rand;
+
+mod common;
+
+use life::automaton::Automaton;
+use life::game;
+use life::hashlife::Universe;
+use life::seed;
+
+use rand::Rng;
+
+use common::cells;
+
+// Big enough that nothing a 16x16 soup sends out reaches the edge within 64 generations.
+const SIZE: usize = 160;
+
+fn soup(seed: u64) -> game::Board {
+  let mut rng = seed::seeded_rng(seed);
+  let mut board = game::Board::new(SIZE, SIZE);
+  let start = (SIZE / 2 - 8) as i64;
+  for r in start..start + 16 {
+    for c in start..start + 16 {
+      board.set(r, c, rng.gen::<f32>() < 0.4);
+    }
+  }
+  board
+}
+
+fn stepped(board: &game::Board, generations: u64) -> game::Board {
+  let mut board = board.clone();
+  for _ in 0..generations {
+    board.step();
+  }
+  board
+}
+
+#[test]
+fn advance_matches_game_board() {
+  for seed in 0..4 {
+    let board = soup(seed);
+    for &n in &[1, 2, 3, 7, 16, 37, 64] {
+      let mut universe = Universe::from_board(&board);
+      universe.advance(n);
+      assert_eq!(universe.generation, n);
+      assert_eq!(cells(&universe), cells(&stepped(&board, n)), "seed {}, {} generations", seed, n);
+    }
+  }
+}
+
+#[test]
+fn step_pow2_matches_game_board() {
+  let mut board = soup(4);
+  let mut universe = Universe::from_board(&board);
+  for k in 0..6 {
+    universe.step_pow2(k);
+    board = stepped(&board, 1 << k);
+    assert_eq!(cells(&universe), cells(&board), "2^{}", k);
+  }
+}
+
+#[test]
+fn garbage_collection_keeps_the_pattern() {
+  let mut board = soup(5);
+  let mut universe = Universe::from_board(&board).with_node_limit(100);
+  for generation in 1..65 {
+    universe.step();
+    board.step();
+    assert_eq!(cells(&universe), cells(&board), "generation {}", generation);
+  }
+}
+
+#[test]
+#[should_panic]
+fn birth_on_zero_neighbors_is_rejected() {
+  Universe::new().with_rule("B03/S23".parse().unwrap());
+}
@@ -0,0 +1,159 @@
+// Going back, jumping around and replaying must always give the same boards as stepping forward
+// did, whatever is kept as keyframes and however much has been dropped.
+
+extern crate life;
+
+mod common;
+
+use life::automaton::Automaton;
+use life::game::Board;
+use life::history::History;
+use life::seed;
+use life::topology::Topology;
+
+use common::cells;
+
+const GENERATIONS: u64 = 40;
+
+fn start() -> Board {
+  let mut rng = seed::seeded_rng(7);
+  Board::random(24, 24, 0.4, &mut rng).with_topology(Topology::Torus)
+}
+
+// The live cells of the start board in each generation up to GENERATIONS.
+fn expected() -> Vec<Vec<(i64, i64)>> {
+  let mut board = start();
+  let mut expected = vec![cells(&board)];
+  for _ in 0..GENERATIONS {
+    board.step();
+    expected.push(cells(&board));
+  }
+  expected
+}
+
+fn run(history: &mut History<Board>) {
+  for _ in 0..GENERATIONS {
+    history.step();
+  }
+}
+
+#[test]
+fn back_to_the_oldest_state() {
+  let expected = expected();
+  let mut history = History::new(start()).with_keyframe_interval(7);
+  run(&mut history);
+  let mut backs = 0;
+  while history.back() {
+    backs += 1;
+    let generation = history.generation();
+    assert_eq!(cells(history.board()), expected[generation as usize]);
+  }
+  assert_eq!(backs, GENERATIONS);
+  assert_eq!(history.generation(), 0);
+}
+
+#[test]
+fn goto_every_generation() {
+  let expected = expected();
+  let mut history = History::new(start()).with_keyframe_interval(7);
+  run(&mut history);
+  // Back and forth across the whole range, so seeks start from keyframes on either side.
+  let order = (0..GENERATIONS + 1).rev().chain((0..GENERATIONS + 1).map(|g| (g * 17) % 41));
+  for generation in order {
+    history.goto(generation).unwrap();
+    assert_eq!(history.generation(), generation);
+    assert_eq!(cells(history.board()), expected[generation as usize], "generation {}", generation);
+  }
+}
+
+#[test]
+fn replay_after_back() {
+  let mut history = History::new(start()).with_keyframe_interval(3);
+  for _ in 0..5 {
+    history.step();
+  }
+  history.edit(|board| board.set(0, 0, true));
+  for _ in 0..5 {
+    history.step();
+  }
+  let latest = cells(history.board());
+
+  for _ in 0..9 {
+    assert!(history.back());
+  }
+  // Stepping forward again replays the edit rather than stepping the board.
+  for _ in 0..9 {
+    history.step();
+  }
+  assert_eq!(history.generation(), 10);
+  assert_eq!(cells(history.board()), latest);
+  assert_eq!(history.range(), (0, 10));
+}
+
+#[test]
+fn consecutive_edits_are_undone_together() {
+  let expected = expected();
+  let mut history = History::new(start());
+  for _ in 0..3 {
+    history.step();
+  }
+  history.edit(|board| board.set(0, 0, !board.get(0, 0)));
+  history.edit(|board| board.set(5, 5, !board.get(5, 5)));
+  assert!(cells(history.board()) != expected[3]);
+
+  assert!(history.back());
+  assert_eq!(history.generation(), 3);
+  assert_eq!(cells(history.board()), expected[3]);
+  assert!(history.back());
+  assert_eq!(cells(history.board()), expected[2]);
+}
+
+#[test]
+fn capacity_is_never_exceeded() {
+  let expected = expected();
+  for &capacity in &[1, 2, 3, 5, 10] {
+    for &interval in &[1, 4, 100] {
+      let mut history = History::new(start())
+        .with_capacity(capacity)
+        .with_keyframe_interval(interval);
+      run(&mut history);
+      let (first, last) = history.range();
+      assert_eq!(last, GENERATIONS);
+      assert!(last - first < capacity as u64, "capacity {}, interval {}: kept {}..{}",
+              capacity, interval, first, last);
+
+      history.goto(first).unwrap();
+      assert_eq!(cells(history.board()), expected[first as usize]);
+      assert!(!history.back());
+      if first > 0 {
+        assert!(history.goto(first - 1).is_err());
+      }
+    }
+  }
+}
+
+#[test]
+fn goto_steps_at_most_capacity_ahead() {
+  let mut history = History::new(start()).with_capacity(10);
+  run(&mut history);
+  history.goto(GENERATIONS + 10).unwrap();
+  assert_eq!(history.generation(), GENERATIONS + 10);
+  assert_eq!(history.range().1, GENERATIONS + 10);
+
+  for &generation in &[GENERATIONS + 21, u64::max_value()] {
+    assert!(history.goto(generation).is_err());
+    assert_eq!(history.generation(), GENERATIONS + 10);
+  }
+}
+
+#[test]
+#[should_panic]
+fn zero_capacity_is_rejected() {
+  History::new(start()).with_capacity(0);
+}
+
+#[test]
+#[should_panic]
+fn zero_keyframe_interval_is_rejected() {
+  History::new(start()).with_keyframe_interval(0);
+}
@@ -0,0 +1,61 @@
+// ndgame::Board steps whole arrays at once, with a border standing in for the topology, so it must
+// agree with game::Board's cell by cell lookups on every topology. Splitting it into bands of rows
+// on several threads mustn't change the result either.
+
+extern crate life;
+
+mod common;
+
+use life::automaton::Automaton;
+use life::game;
+use life::ndgame;
+use life::rule::Rule;
+use life::seed;
+
+use common::{cells, TOPOLOGIES};
+
+#[test]
+fn matches_game_board() {
+  for &topology in TOPOLOGIES.iter() {
+    let mut rng = seed::seeded_rng(11);
+    let mut board = game::Board::random(23, 31, 0.4, &mut rng).with_topology(topology);
+    let mut nd = ndgame::Board::blank(23, 31).with_topology(topology);
+    for (r, c) in board.live_cells() {
+      nd.set(r, c, true);
+    }
+
+    for generation in 1..41 {
+      board.step();
+      nd.step();
+      assert_eq!(cells(&nd), cells(&board), "{:?}, generation {}", topology, generation);
+    }
+  }
+}
+
+#[test]
+fn parallel_matches_serial() {
+  let brain: Rule = "B2/S/C3".parse().unwrap();
+  for &rule in &[Rule::conway(), brain] {
+    for &topology in TOPOLOGIES.iter() {
+      // Row counts that don't divide evenly between the threads.
+      for &rows in &[7, 13] {
+        for &threads in &[1, 3, rows + 5] {
+          let mut rng = seed::seeded_rng(rows as u64);
+          let mut serial = ndgame::Board::random(rows, 19, 0.4, &mut rng)
+            .with_topology(topology)
+            .with_rule(rule);
+          let mut parallel = serial.clone();
+          let mut scratch = ndgame::Board::scratch(rows, 19);
+          let mut next = ndgame::Board::scratch(rows, 19);
+
+          for generation in 1..21 {
+            serial.iterate(&mut scratch);
+            parallel.iterate_parallel(&mut next, threads);
+            assert!(parallel.arr == serial.arr, "{}, {:?}, {} rows, {} threads, generation {}",
+                    rule, topology, rows, threads, generation);
+          }
+        }
+      }
+    }
+  }
+}
@@ -0,0 +1,67 @@
+// Larger than Life, von Neumann and hexagonal rules: game::Board counts each cell's neighbors from
+// the neighborhood's offsets, while ndgame::Board counts range 1 neighborhoods by shifting arrays
+// and larger ones from summed-area tables, so they must agree on every topology.
+
+extern crate life;
+
+mod common;
+
+use life::automaton::Automaton;
+use life::game;
+use life::ndgame;
+use life::neighborhood::Neighborhood;
+use life::rule::Rule;
+use life::seed;
+
+use common::{cells, TOPOLOGIES};
+
+#[test]
+fn offsets() {
+  for range in 1..5 {
+    let r = range as usize;
+    assert_eq!(Neighborhood::Moore(range).offsets().len(), (2 * r + 1) * (2 * r + 1) - 1);
+    assert_eq!(Neighborhood::VonNeumann(range).offsets().len(), 2 * r * (r + 1));
+  }
+  assert_eq!(Neighborhood::Moore(1).offsets(),
+             vec![(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0), (1, 1)]);
+  assert_eq!(Neighborhood::VonNeumann(1).offsets(), vec![(-1, 0), (0, -1), (0, 1), (1, 0)]);
+  assert_eq!(Neighborhood::Hexagonal.offsets(),
+             vec![(-1, -1), (-1, 0), (0, -1), (0, 1), (1, 0), (1, 1)]);
+  assert!(Neighborhood::VonNeumann(3).offsets().contains(&(-1, 2)));
+  assert!(!Neighborhood::VonNeumann(3).offsets().contains(&(-2, 2)));
+}
+
+#[test]
+fn ndgame_matches_game_board() {
+  let rules = [
+    // Bosco's Rule: a range 5 square that counts the middle cell.
+    "R5,C0,M1,S34..58,B34..45,NM",
+    "R2,C0,M0,S4..9,B6..8,NM",
+    "R3,C0,M0,S3..8,B5..7,NN",
+    "R2,C0,M1,S2..4,B3..4,NN",
+    "B3/S23V",
+    "B2/S34H",
+  ];
+  for rule in rules.iter() {
+    let parsed: Rule = rule.parse().unwrap();
+    for &topology in TOPOLOGIES.iter() {
+      let mut rng = seed::seeded_rng(17);
+      let mut board = game::Board::random(23, 31, 0.5, &mut rng)
+        .with_topology(topology)
+        .with_rule(parsed);
+      let mut nd = ndgame::Board::blank(23, 31).with_topology(topology).with_rule(parsed);
+      for (r, c) in board.live_cells() {
+        nd.set(r, c, true);
+      }
+
+      for generation in 1..21 {
+        board.step();
+        nd.step();
+        assert_eq!(cells(&nd), cells(&board), "{}, {:?}, generation {}", rule, topology,
+                   generation);
+      }
+      // Otherwise the boards could agree by both being empty.
+      assert!(board.population() > 0, "{}, {:?} died out", rule, topology);
+    }
+  }
+}
@@ -0,0 +1,160 @@
+// Reading and writing RLE files: headers and comment lines, run lengths, wrapping long bodies,
+// multi-state prefixes, and rejecting malformed files without panicking.
+
+extern crate life;
+
+use life::format::rle;
+use life::rule::Rule;
+
+const GLIDER: &'static str = "#N Glider
+#O Richard K. Guy
+#C The smallest, most common, and first discovered spaceship.
+x = 3, y = 3, rule = B3/S23
+bo$2bo$3o!
+";
+
+const GOSPER_GUN: &'static str = "#N Gosper glider gun
+x = 36, y = 9, rule = B3/S23
+24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$2o8bo5bo3b2o$2o8bo3bob2o4b
+obo$10bo5bo7bo$11bo3bo$12b2o!
+";
+
+#[test]
+fn glider() {
+  let pattern = rle::read(GLIDER).unwrap();
+  assert_eq!(pattern.name, Some("Glider".to_string()));
+  assert_eq!(pattern.author, Some("Richard K. Guy".to_string()));
+  assert_eq!(pattern.comments,
+             vec!["The smallest, most common, and first discovered spaceship.".to_string()]);
+  assert_eq!(pattern.rule, Some(Rule::conway()));
+  assert_eq!((pattern.width, pattern.height), (3, 3));
+  assert_eq!(pattern.cells, vec![(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]);
+  assert!(pattern.decaying.is_empty());
+
+  assert_eq!(rle::write(&pattern), GLIDER);
+}
+
+#[test]
+fn gosper_gun_round_trip() {
+  let pattern = rle::read(GOSPER_GUN).unwrap();
+  assert_eq!((pattern.width, pattern.height), (36, 9));
+  assert_eq!(pattern.cells.len(), 36);
+  assert_eq!(pattern.cells[0], (0, 24));
+  assert!(pattern.cells.contains(&(4, 0)) && pattern.cells.contains(&(3, 35)));
+  assert!(pattern.cells.contains(&(8, 13)) && !pattern.cells.contains(&(8, 14)));
+
+  let written = rle::write(&pattern);
+  assert_eq!(rle::read(&written).unwrap(), pattern);
+}
+
+#[test]
+fn header_and_comments() {
+  // `#c` comments, `#r` rules, unknown lines and blank lines before the header, and a header
+  // without spaces. Fields the reader doesn't know are skipped.
+  let input = "#c lower case\n\n#r 23/36\n#P 1 1\n#C upper case\nx=2,y=1,foo=bar\n2o!";
+  let pattern = rle::read(input).unwrap();
+  assert_eq!(pattern.comments, vec!["lower case".to_string(), "upper case".to_string()]);
+  assert_eq!(pattern.rule, Some("B36/S23".parse().unwrap()));
+  assert_eq!(pattern.name, None);
+  assert_eq!(pattern.cells, vec![(0, 0), (0, 1)]);
+
+  // The header's rule takes precedence over `#r`, and no rule at all leaves it unset.
+  let pattern = rle::read("#r B36/S23\nx = 1, y = 1, rule = B2/S\no!").unwrap();
+  assert_eq!(pattern.rule, Some("B2/S".parse().unwrap()));
+  assert_eq!(rle::read("x = 1, y = 1\no!").unwrap().rule, None);
+
+  // Anything after the end of the pattern is ignored.
+  let pattern = rle::read("x = 1, y = 1\no!\nthis is not RLE").unwrap();
+  assert_eq!(pattern.cells, vec![(0, 0)]);
+}
+
+#[test]
+fn run_lengths() {
+  let pattern = rle::read("x = 5, y = 4\n2bo2$3o$\n4b\no!").unwrap();
+  assert_eq!(pattern.cells, vec![(0, 2), (2, 0), (2, 1), (2, 2), (3, 4)]);
+  assert_eq!((pattern.width, pattern.height), (5, 4));
+
+  // Headers that understate the size grow to fit, and ones that overstate it are kept.
+  let pattern = rle::read("x = 1, y = 1\n3o$o!").unwrap();
+  assert_eq!((pattern.width, pattern.height), (3, 2));
+  let pattern = rle::read("x = 4, y = 3\no!").unwrap();
+  assert_eq!((pattern.width, pattern.height), (4, 3));
+
+  // Other letters are live cells too, and trailing dead cells and empty rows are written as
+  // runs only where they are needed.
+  let pattern = rle::read("x = 6, y = 5\nob2a$$6b$3bz2b!").unwrap();
+  assert_eq!(pattern.cells, vec![(0, 0), (0, 2), (0, 3), (3, 3)]);
+  assert_eq!(rle::write(&pattern), "x = 6, y = 5\nob2o3$3bo!\n");
+}
+
+#[test]
+fn long_lines_are_wrapped() {
+  let mut input = "x = 400, y = 3\n".to_string();
+  for c in 0..400 {
+    input.push_str(if c % 3 == 0 { "2o" } else { "b" });
+  }
+  input.push_str("$$400o!");
+  let pattern = rle::read(&input).unwrap();
+
+  let written = rle::write(&pattern);
+  let lines: Vec<&str> = written.lines().collect();
+  assert!(lines.len() > 3);
+  assert!(lines.iter().all(|line| line.len() <= 70), "{}", written);
+  // Runs aren't split across lines.
+  assert!(lines[1..].iter().all(|line| !line.ends_with(|ch: char| ch.is_digit(10))));
+  assert_eq!(rle::read(&written).unwrap(), pattern);
+}
+
+#[test]
+fn multi_state_prefixes() {
+  let input = "x = 6, y = 2, rule = B2/S/C255\nA.BpA\n2xB$yN!";
+  let pattern = rle::read(input).unwrap();
+  assert_eq!(pattern.cells, vec![(0, 0)]);
+  assert_eq!(pattern.decaying,
+             vec![(0, 2, 2), (0, 3, 25), (0, 4, 218), (0, 5, 218), (1, 0, 254)]);
+
+  let written = rle::write(&pattern);
+  assert_eq!(written, "x = 6, y = 2, rule = B2/S/C255\nA.BpA2xB$yN!\n");
+  assert_eq!(rle::read(&written).unwrap(), pattern);
+
+  // Without a rule there is nothing to check the states against.
+  let pattern = rle::read("x = 2, y = 1\nopA!").unwrap();
+  assert_eq!(pattern.cells, vec![(0, 0)]);
+  assert_eq!(pattern.decaying, vec![(0, 1, 25)]);
+}
+
+#[test]
+fn prefixed_states_must_fit_the_rule() {
+  // Two-state files can contain prefixes, but not states their rule doesn't have.
+  assert!(rle::read("x = 2, y = 1, rule = B3/S23\nopA!").is_err());
+  assert!(rle::read("x = 2, y = 1, rule = B3/S23\nB!").is_err());
+  assert!(rle::read("x = 2, y = 1, rule = B2/S/C3\nAC!").is_err());
+  assert!(rle::read("x = 2, y = 1, rule = B2/S/C3\nAB!").is_ok());
+  // Two states written with letters are ordinary live cells.
+  assert_eq!(rle::read("x = 2, y = 1, rule = B3/S23\n.A!").unwrap().cells, vec![(0, 1)]);
+}
+
+#[test]
+fn malformed_input_is_an_error() {
+  let inputs = [
+    "",
+    "#N Only comments",
+    "bo$2bo$3o!",
+    "x = a, y = 3\no!",
+    "x = 3 y = 3\no!",
+    "x = 3, y = 3, rule = B9/S\no!",
+    "x = 3, y = 3, rule\no!",
+    "x = 1, y = 1\no#!",
+    "x = 1, y = 1\n-1o!",
+    "x = 1, y = 1\npb!",
+    "x = 1, y = 1\npp!",
+    "x = 1, y = 1\np!",
+    "x = 1, y = 1\nyY!",
+    "x = 1, y = 1\n99999999999999999999999999o!",
+    "x = 1, y = 1\n16777216b2o!",
+    "x = 1, y = 1\n9999999$9999999$9999999$o!",
+  ];
+  for input in inputs.iter() {
+    assert!(rle::read(input).is_err(), "{:?} was read", input);
+  }
+}
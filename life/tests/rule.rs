@@ -0,0 +1,106 @@
+// Parsing rules in each of the notations Rule accepts, and writing them back out in a form that
+// parses to the same rule.
+
+extern crate life;
+
+use life::neighborhood::Neighborhood;
+use life::rule::Rule;
+
+fn parse(s: &str) -> Rule {
+  s.parse().unwrap_or_else(|e| panic!("{}: {}", s, e))
+}
+
+#[test]
+fn birth_and_survival() {
+  let highlife = parse("B36/S23");
+  assert!(highlife.born(3) && highlife.born(6) && !highlife.born(2));
+  assert!(highlife.survives(2) && highlife.survives(3) && !highlife.survives(6));
+  assert_eq!(highlife.states(), 2);
+  assert!(highlife.is_life_like());
+  assert_eq!(highlife, Rule::new(&[3, 6], &[2, 3]));
+
+  // Either order, any case, and surrounding whitespace.
+  assert_eq!(parse("S23/B36"), highlife);
+  assert_eq!(parse(" b36/s23 "), highlife);
+  assert_eq!(parse("B3/S23"), Rule::conway());
+  assert_eq!(parse("B/S"), Rule::new(&[], &[]));
+}
+
+#[test]
+fn legacy_survival_then_birth() {
+  assert_eq!(parse("23/3"), Rule::conway());
+  assert_eq!(parse("23/36"), parse("B36/S23"));
+  assert_eq!(parse("/2"), parse("B2/S"));
+}
+
+#[test]
+fn generations() {
+  let brain = parse("B2/S/C3");
+  assert_eq!(brain.states(), 3);
+  assert!(!brain.is_life_like());
+  assert_eq!(parse("/2/3"), brain);
+  assert_eq!(parse("C3/B2/S"), brain);
+  // Two states is an ordinary Life-like rule.
+  assert_eq!(parse("B3/S23/C2"), Rule::conway());
+}
+
+#[test]
+fn other_neighborhoods() {
+  let hex = parse("B2/S34H");
+  assert_eq!(hex.neighborhood(), Neighborhood::Hexagonal);
+  assert_eq!(hex, Rule::new(&[2], &[3, 4]).with_neighborhood(Neighborhood::Hexagonal));
+  assert!(!hex.is_life_like());
+  assert_eq!(parse("B1/S1v").neighborhood(), Neighborhood::VonNeumann(1));
+}
+
+#[test]
+fn larger_than_life() {
+  let bosco = parse("R5,C0,M1,S34..58,B34..45,NM");
+  assert_eq!(bosco.neighborhood(), Neighborhood::Moore(5));
+  assert_eq!(bosco.states(), 2);
+  assert!(bosco.middle());
+  assert!(!bosco.born(33) && bosco.born(34) && bosco.born(45) && !bosco.born(46));
+  assert!(!bosco.survives(33) && bosco.survives(34) && bosco.survives(58) && !bosco.survives(59));
+  assert!(!bosco.is_life_like());
+
+  // Ranges can be written with a dash, fields in any case, and the neighborhood left out.
+  assert_eq!(parse("r5,c0,m1,s34-58,b34-45"), bosco);
+
+  let diamond = parse("R2,C3,M0,S2..3,5,B3,NN");
+  assert_eq!(diamond.neighborhood(), Neighborhood::VonNeumann(2));
+  assert_eq!(diamond.states(), 3);
+  assert!(!diamond.middle());
+  assert!(diamond.survives(2) && diamond.survives(3) && !diamond.survives(4));
+  assert!(diamond.survives(5));
+  assert!(diamond.born(3) && !diamond.born(2));
+}
+
+#[test]
+fn invalid_rules() {
+  for s in &["", "B3", "B3/S23/C3/X", "B9/S23", "B3/Sx", "B3/23", "B3/S23/C1", "B3/S23/3",
+             "R0,C0,M0,S1,B1,NM", "R8,C0,M0,S1,B1,NM", "C0,M0,S1,B1", "R2,C0,M2,S1,B1",
+             "R2,C0,M0,S1,B1,NX", "R2,C0,M0,S1..2..3,B1", "R2,C0,M0,Sx,B1", "R2,X1"] {
+    assert!(s.parse::<Rule>().is_err(), "{:?} parsed", s);
+  }
+}
+
+#[test]
+fn display() {
+  assert_eq!(Rule::conway().to_string(), "B3/S23");
+  assert_eq!(parse("23/36").to_string(), "B36/S23");
+  assert_eq!(parse("/2/3").to_string(), "B2/S/C3");
+  assert_eq!(parse("B2/S34H").to_string(), "B2/S34H");
+  assert_eq!(parse("R5,C0,M1,S34-58,B34-45").to_string(), "R5,C0,M1,S34..58,B34..45,NM");
+  assert_eq!(parse("R2,C3,M0,S2..3,5,B3,NN").to_string(), "R2,C3,M0,S2..3,5,B3,NN");
+}
+
+#[test]
+fn display_round_trips() {
+  for s in &["B3/S23", "S23/B36", "B/S", "B012345678/S012345678", "23/3", "B2/S/C3", "345/2/4",
+             "B2/S34H", "B1/S1V", "B2/S/C5V", "R5,C0,M1,S34..58,B34..45,NM",
+             "R2,C3,M0,S2..3,5,B3,NN", "R1,C0,M1,S3..4,B3,NM", "R3,C0,M0,S0,B9..12,NN"] {
+    let rule = parse(s);
+    let written = rule.to_string();
+    assert_eq!(parse(&written), rule, "{} was written as {}", s, written);
+  }
+}
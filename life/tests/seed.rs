@@ -0,0 +1,78 @@
+// Runs seeded with the same seed must come out exactly the same.
+
+extern crate life;
+extern crate optimizer;
+
+use life::census::Catalogue;
+use life::format::PatternFile;
+use life::game::Board;
+use life::ndgame;
+use life::pattern_finder::{Pattern, PatternSearch, Symmetry};
+use life::rule::Rule;
+use life::seed::seeded_rng;
+use life::soup::Soup;
+
+use optimizer::Neighbor;
+use optimizer::annealing::{Annealer, Schedule};
+
+struct TestSchedule;
+impl Schedule for TestSchedule {
+  fn temp(step: u32, _step_max: u32) -> f64 {
+    0.01 / (1. + step as f64)
+  }
+}
+
+fn random_board(seed: u64) -> PatternFile {
+  PatternFile::from_board(&Board::random(50, 50, 0.4, &mut seeded_rng(seed)))
+}
+
+fn anneal(seed: u64) -> PatternFile {
+  let mut rng = seeded_rng(seed);
+  let search = PatternSearch::new().with_candidate_size(6);
+  let start = Pattern::random(Board::new(30, 30), search, &mut rng);
+  let mut annealer = Annealer::new(start);
+  annealer.optimize::<TestSchedule, _>(100, &mut rng).to_pattern_file()
+}
+
+#[test]
+fn game_board_random() {
+  assert_eq!(random_board(1), random_board(1));
+  assert!(random_board(1) != random_board(2));
+}
+
+#[test]
+fn ndgame_board_random() {
+  let a = ndgame::Board::random(40, 40, 0.5, &mut seeded_rng(7));
+  let b = ndgame::Board::random(40, 40, 0.5, &mut seeded_rng(7));
+  assert_eq!(a.arr, b.arr);
+}
+
+#[test]
+fn pattern_random_and_neighbor() {
+  let pattern = |seed| {
+    let mut rng = seeded_rng(seed);
+    let search = PatternSearch::new().with_symmetry(Symmetry::D4).with_flips(3);
+    let mut p = Pattern::random(Board::new(20, 20), search, &mut rng);
+    for _ in 0..20 {
+      p = p.neighbor(&mut rng);
+    }
+    p.to_pattern_file()
+  };
+  assert_eq!(pattern(3), pattern(3));
+  assert!(pattern(3) != pattern(4));
+}
+
+#[test]
+fn annealer_optimize() {
+  assert_eq!(anneal(5), anneal(5));
+}
+
+#[test]
+fn soup() {
+  let catalogue = Catalogue::new(Rule::conway());
+  let a = Soup::run(11, 16, 0.5, &catalogue, 5000);
+  let b = Soup::run(11, 16, 0.5, &catalogue, 5000);
+  assert_eq!(PatternFile::from_board(&a.pattern), PatternFile::from_board(&b.pattern));
+  assert_eq!(a.lifespan, b.lifespan);
+  assert_eq!(a.census.counts, b.census.counts);
+}
@@ -0,0 +1,79 @@
+// Classifying spaceships by speed and direction, whichever way they are facing, and not mistaking
+// anything else for one.
+
+extern crate life;
+
+mod common;
+
+use life::automaton::Automaton;
+use life::spaceship::{self, Direction, Velocity};
+use life::sparse::Board;
+
+use common::rle_board;
+
+// `board` reflected top to bottom and/or left to right.
+fn flipped(board: &Board, rows: bool, cols: bool) -> Board {
+  let mut flipped = Board::new();
+  for (r, c) in board.live_cells() {
+    flipped.set(if rows { -r } else { r }, if cols { -c } else { c }, true);
+  }
+  flipped
+}
+
+fn classify(board: &Board) -> Option<Velocity> {
+  spaceship::classify(board, 100)
+}
+
+#[test]
+fn glider() {
+  let glider = rle_board("bo$2bo$3o!");
+  for &(rows, cols) in &[(false, false), (false, true), (true, false), (true, true)] {
+    let velocity = classify(&flipped(&glider, rows, cols)).unwrap();
+    let sign = |flip| if flip { -1 } else { 1 };
+    assert_eq!(velocity, Velocity { displacement: (sign(rows), sign(cols)), period: 4 });
+    assert_eq!(velocity.direction(), Direction::Diagonal);
+    assert_eq!(velocity.speed(), (1, 4));
+    assert_eq!(velocity.to_string(), "c/4 diagonal");
+  }
+}
+
+#[test]
+fn lightweight_spaceship() {
+  let lwss = rle_board("bo2bo$o$o3bo$4o!");
+  let velocity = classify(&lwss).unwrap();
+  assert_eq!(velocity, Velocity { displacement: (0, -2), period: 4 });
+  assert_eq!(velocity.direction(), Direction::Orthogonal);
+  assert_eq!(velocity.speed(), (1, 2));
+  assert_eq!(velocity.to_string(), "c/2 orthogonal");
+
+  // Turned on its side, it travels along the columns instead.
+  let mut turned = Board::new();
+  for (r, c) in lwss.live_cells() {
+    turned.set(c, r, true);
+  }
+  let velocity = classify(&turned).unwrap();
+  assert_eq!(velocity.displacement, (-2, 0));
+  assert_eq!(velocity.to_string(), "c/2 orthogonal");
+}
+
+#[test]
+fn not_spaceships() {
+  for body in &["2o$2o!", "3o!", "2o!", "2o$o!"] {
+    assert_eq!(classify(&rle_board(body)), None, "{}", body);
+  }
+  // Nor is anything that hasn't repeated yet.
+  assert_eq!(spaceship::classify(&rle_board("bo$2bo$3o!"), 3), None);
+}
+
+#[test]
+fn speeds() {
+  let velocity = |dr, dc, period| Velocity { displacement: (dr, dc), period: period };
+  assert_eq!(velocity(0, 2, 5).to_string(), "2c/5 orthogonal");
+  assert_eq!(velocity(-4, 0, 8).to_string(), "c/2 orthogonal");
+  assert_eq!(velocity(3, -3, 12).to_string(), "c/4 diagonal");
+
+  let knightwise = velocity(2, -1, 6);
+  assert_eq!(knightwise.direction(), Direction::Oblique);
+  assert_eq!(knightwise.speed(), (1, 3));
+  assert_eq!(knightwise.to_string(), "(2,1)c/6 oblique");
+}
@@ -0,0 +1,95 @@
+// sparse::Board stores the plane as tiles, so it must agree with game::Board as patterns cross
+// from one tile into the next, including into the tiles at negative coordinates.
+
+extern crate life;
+extern crate rand;
+
+mod common;
+
+use life::automaton::Automaton;
+use life::game;
+use life::rule::Rule;
+use life::seed;
+use life::sparse;
+
+use rand::Rng;
+
+use common::cells;
+
+// Big enough that nothing a 16x16 soup in the middle sends out reaches the edge within 40
+// generations.
+const SIZE: usize = 100;
+const MIDDLE: i64 = (SIZE / 2) as i64;
+
+// The live cells of the game::Board, moved so its middle is at (0, 0).
+fn centered(board: &game::Board) -> Vec<(i64, i64)> {
+  cells(board).into_iter().map(|(r, c)| (r - MIDDLE, c - MIDDLE)).collect()
+}
+
+// Sets the same cells, given relative to the middle of the game::Board, on both boards.
+fn place(board: &mut game::Board, sparse: &mut sparse::Board, points: &[(i64, i64)]) {
+  for &(r, c) in points.iter() {
+    board.set(r + MIDDLE, c + MIDDLE, true);
+    sparse.set(r, c, true);
+  }
+}
+
+fn run(mut board: game::Board, mut sparse: sparse::Board, generations: u64, name: &str) {
+  for generation in 1..generations + 1 {
+    board.step();
+    sparse.step();
+    assert_eq!(cells(&sparse), centered(&board), "{}, generation {}", name, generation);
+    assert_eq!(sparse.generation(), generation);
+  }
+}
+
+#[test]
+fn gliders_cross_tiles_in_every_direction() {
+  // A glider heading down and right, starting on the corner between four tiles.
+  let glider = [(-1, 0), (0, 1), (1, -1), (1, 0), (1, 1)];
+  for &(flip_r, flip_c) in &[(1, 1), (1, -1), (-1, 1), (-1, -1)] {
+    let points: Vec<(i64, i64)> = glider.iter().map(|&(r, c)| (r * flip_r, c * flip_c)).collect();
+    let mut board = game::Board::new(SIZE, SIZE);
+    let mut sparse = sparse::Board::new();
+    place(&mut board, &mut sparse, &points);
+    // Far enough to cross a whole tile in each direction.
+    run(board, sparse, 80, &format!("glider {:?}", (flip_r, flip_c)));
+  }
+}
+
+#[test]
+fn soups_around_the_origin() {
+  let highlife: Rule = "B36/S23".parse().unwrap();
+  for &rule in &[Rule::conway(), highlife] {
+    for seed in 0..3 {
+      let mut rng = seed::seeded_rng(seed);
+      let mut board = game::Board::new(SIZE, SIZE).with_rule(rule);
+      let mut sparse = sparse::Board::new().with_rule(rule);
+      let mut points = Vec::new();
+      for r in -8..8 {
+        for c in -8..8 {
+          if rng.gen::<f32>() < 0.4 {
+            points.push((r, c));
+          }
+        }
+      }
+      place(&mut board, &mut sparse, &points);
+      run(board, sparse, 40, &format!("{}, seed {}", rule, seed));
+    }
+  }
+}
+
+#[test]
+fn from_board_keeps_the_cells() {
+  let mut rng = seed::seeded_rng(5);
+  let board = game::Board::random(40, 50, 0.3, &mut rng);
+  let sparse = sparse::Board::from_board(&board);
+  assert_eq!(cells(&sparse), cells(&board));
+  assert_eq!(sparse.population(), board.population());
+}
+
+#[test]
+#[should_panic]
+fn birth_on_zero_neighbors_is_rejected() {
+  sparse::Board::new().with_rule("B03/S23".parse().unwrap());
+}
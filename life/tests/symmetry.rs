@@ -0,0 +1,89 @@
+// Candidates constrained to a symmetry must keep it, both when they are made and when they are
+// changed into their neighbors, on grids with and without a middle row and column.
+
+extern crate life;
+extern crate optimizer;
+
+use std::collections::BTreeSet;
+
+use life::game::Board;
+use life::pattern_finder::{Pattern, PatternSearch, Symmetry};
+use life::seed::seeded_rng;
+
+use optimizer::Neighbor;
+
+type Transform = fn(usize, usize, usize) -> (usize, usize);
+
+fn half_turn(r: usize, c: usize, n: usize) -> (usize, usize) { (n - 1 - r, n - 1 - c) }
+fn quarter_turn(r: usize, c: usize, n: usize) -> (usize, usize) { (c, n - 1 - r) }
+fn mirror_columns(r: usize, c: usize, n: usize) -> (usize, usize) { (r, n - 1 - c) }
+fn mirror_rows(r: usize, c: usize, n: usize) -> (usize, usize) { (n - 1 - r, c) }
+fn transpose(r: usize, c: usize, _: usize) -> (usize, usize) { (c, r) }
+
+// Transforms that generate each symmetry's group.
+fn generators(symmetry: Symmetry) -> Vec<Transform> {
+  match symmetry {
+    Symmetry::C1 => vec![],
+    Symmetry::C2 => vec![half_turn],
+    Symmetry::C4 => vec![quarter_turn],
+    Symmetry::D2 => vec![mirror_columns],
+    Symmetry::D4 => vec![mirror_columns, mirror_rows],
+    Symmetry::D8 => vec![mirror_columns, mirror_rows, transpose],
+  }
+}
+
+fn cells(pattern: &Pattern) -> BTreeSet<(usize, usize)> {
+  pattern.to_pattern_file().cells.into_iter().collect()
+}
+
+fn assert_symmetric(pattern: &Pattern, symmetry: Symmetry, size: usize) {
+  let cells = cells(pattern);
+  for transform in generators(symmetry) {
+    let transformed: BTreeSet<(usize, usize)> =
+      cells.iter().map(|&(r, c)| transform(r, c, size)).collect();
+    assert_eq!(transformed, cells, "{:?}, size {}", symmetry, size);
+  }
+}
+
+#[test]
+fn candidates_keep_their_symmetry() {
+  for &symmetry in &[Symmetry::C2, Symmetry::C4, Symmetry::D2, Symmetry::D4, Symmetry::D8] {
+    for &size in &[7, 8] {
+      let mut rng = seeded_rng(size as u64);
+      let search = PatternSearch::new()
+        .with_candidate_size(size)
+        .with_symmetry(symmetry)
+        .with_flips(3);
+      let mut pattern = Pattern::random(Board::new(20, 20), search, &mut rng);
+      assert!(!cells(&pattern).is_empty());
+      assert_symmetric(&pattern, symmetry, size);
+      for _ in 0..50 {
+        pattern = pattern.neighbor(&mut rng);
+        assert_symmetric(&pattern, symmetry, size);
+      }
+    }
+  }
+}
+
+#[test]
+fn orbits_are_closed_under_the_group() {
+  for &symmetry in &[Symmetry::C1, Symmetry::C2, Symmetry::C4, Symmetry::D2, Symmetry::D4,
+                     Symmetry::D8] {
+    for &size in &[7, 8] {
+      for r in 0..size {
+        for c in 0..size {
+          let orbit = symmetry.orbit(r, c, size);
+          assert!(orbit.contains(&(r, c)));
+          for &(r, c) in orbit.iter() {
+            assert_eq!(symmetry.orbit(r, c, size), orbit, "{:?}, size {}", symmetry, size);
+            for transform in generators(symmetry) {
+              assert!(orbit.contains(&transform(r, c, size)), "{:?}, size {}", symmetry, size);
+            }
+          }
+        }
+      }
+    }
+  }
+  // The middle cell of an odd grid is its own orbit under every symmetry.
+  assert_eq!(Symmetry::D8.orbit(3, 3, 7), vec![(3, 3)]);
+}
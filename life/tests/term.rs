@@ -0,0 +1,39 @@
+// Each text style must put a small known pattern's cells into the right characters.
+
+extern crate life;
+
+use life::automaton::Bounds;
+use life::game::Board;
+use life::term::{self, Style};
+
+fn glider() -> Board {
+  Board::parse(b".#..\n..#.\n###.\n....\n")
+}
+
+#[test]
+fn ascii() {
+  let lines = term::render(&glider(), Bounds::new(1, 1, 2, 3), Style::Ascii);
+  assert_eq!(lines, vec![".#.", "##."]);
+}
+
+#[test]
+fn half_blocks() {
+  let lines = term::render(&glider(), Bounds::new(0, 0, 4, 4), Style::HalfBlock);
+  assert_eq!(lines, vec![" \u{2580}\u{2584} ", "\u{2580}\u{2580}\u{2580} "]);
+
+  // Cells below the view are left out of its last character row.
+  let lines = term::render(&glider(), Bounds::new(0, 0, 3, 4), Style::HalfBlock);
+  assert_eq!(lines, vec![" \u{2580}\u{2584} ", "\u{2580}\u{2580}\u{2580} "]);
+  let lines = term::render(&glider(), Bounds::new(1, 0, 1, 4), Style::HalfBlock);
+  assert_eq!(lines, vec!["  \u{2580} "]);
+}
+
+#[test]
+fn braille() {
+  // Dots 4, 3 and 6 on the left, and 2 and 3 on the right.
+  let lines = term::render(&glider(), Bounds::new(0, 0, 4, 4), Style::Braille);
+  assert_eq!(lines, vec!["\u{282c}\u{2806}"]);
+
+  let lines = term::render(&glider(), Bounds::new(0, 0, 2, 4), Style::Braille);
+  assert_eq!(lines, vec!["\u{2808}\u{2802}"]);
+}
@@ -1,25 +1,29 @@
 use Cost;
 use Neighbor;
 
-extern crate rand;
-use annealing::rand::Rng;
+use rand::Rng;
 
 pub trait Schedule {
   fn temp(step: u32, step_max: u32) -> f64;
 }
 
-pub struct Annealer<'a, T: Cost + Neighbor + 'a> {
+pub struct Annealer<'a, T: Cost + Neighbor + Clone + 'a> {
   state: T,
   energy: f64,
+  // The lowest energy state visited so far.
+  best: T,
+  best_energy: f64,
   visit_cb: Box<FnMut(&T) + 'a>
 }
 
 fn default_cb<T>(_: &T) {}
 
-impl<'a, T> Annealer<'a, T> where T: Cost + Neighbor {
+impl<'a, T> Annealer<'a, T> where T: Cost + Neighbor + Clone {
   pub fn new(start: T) -> Annealer<'a, T> {
     let energy = start.cost();
     Annealer {
+      best: start.clone(),
+      best_energy: energy,
       state: start,
       energy: energy,
       visit_cb: Box::new(default_cb::<T>)
@@ -30,11 +34,11 @@ impl<'a, T> Annealer<'a, T> where T: Cost + Neighbor {
     self.visit_cb = cb;
   }
 
-  pub fn optimize<S: Schedule>(&mut self, steps: u32) -> &T {
+  // Anneals for `steps` steps, drawing every random choice from `rng`.
+  pub fn optimize<S: Schedule, R: Rng>(&mut self, steps: u32, rng: &mut R) -> &T {
     let mut visits = 0;
-    let mut rng = rand::thread_rng();
     for step in 0..steps {
-      let neighbor = self.state.neighbor();
+      let neighbor = self.state.neighbor(rng);
       let neighbor_energy = neighbor.cost();
       let temp = S::temp(step, steps);
       let x : f64 = rng.gen();
@@ -43,6 +47,10 @@ impl<'a, T> Annealer<'a, T> where T: Cost + Neighbor {
         self.state = neighbor;
         self.energy = neighbor_energy;
         visits += 1;
+        if self.energy < self.best_energy {
+          self.best = self.state.clone();
+          self.best_energy = self.energy;
+        }
         (self.visit_cb)(&self.state);
       }
 
@@ -54,6 +62,12 @@ impl<'a, T> Annealer<'a, T> where T: Cost + Neighbor {
     &self.state
   }
 
+  // The best state found by `optimize` so far and its energy. The annealer can wander away from
+  // it, so this may be better than the state `optimize` finishes in.
+  pub fn best(&self) -> (&T, f64) {
+    (&self.best, self.best_energy)
+  }
+
   fn acceptance(e_old: f64, e_new: f64, temp: f64) -> f64 {
     if e_new < e_old {
       1.
@@ -1,8 +1,12 @@
+extern crate rand;
+use rand::Rng;
+
 pub trait Cost {
   fn cost(&self) -> f64;
 }
 pub trait Neighbor {
-  fn neighbor(&self) -> Self;
+  // Draws all its randomness from `rng`, so a seeded search can be replayed.
+  fn neighbor<R: Rng>(&self, rng: &mut R) -> Self;
 }
 
 //pub struct Annealer<T: Cost + Neighbor> {